@@ -1,123 +1,496 @@
 use axum::Router;
+use chrono::{DateTime, NaiveDate, Utc};
 use futures::lock::Mutex;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use razer_core::{Admin, AdminError, FieldConfig, Resource, ValidationResult};
-use razer_ui::render_toggle_widget;
-use razer_core_derive::AdminResource;
+use razer_core::{
+    Admin, AdminDataSource, AdminError, FieldConfig, ListPage, ListQuery, RelatedList,
+    SortDirection, ValidationErrors,
+};
+use razer_core_derive::{AdminResource, AdminSelect};
+use razer_ui::{render_recent_items, render_stat_tile, render_toggle_widget};
+
+use address::{Address, AddressInput};
 
 #[derive(Clone)]
 struct AdminContext {
     my_models: Arc<Mutex<Vec<MyModel>>>,
+    gifts: Arc<Mutex<Vec<Gift>>>,
+    addresses: Arc<Mutex<Vec<Address>>>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, AdminResource)]
-#[admin(name = "abc")]
+#[admin(
+    name = "My Model",
+    path = "model",
+    label = "name",
+    description = "People we know about",
+    create_input = "MyModelInput"
+)]
 struct MyModel {
-    #[admin(readonly)]
+    #[admin(readonly, sortable)]
     id: i32,
+    #[admin(sortable, searchable, required, length(max = 64))]
     name: String,
+    #[admin(length(max = 32))]
+    nickname: Option<String>,
+    #[admin(min = 0)]
+    height: f64,
+    #[admin(format = "%-d %B %Y")]
+    birthday: Option<NaiveDate>,
+    #[admin(widget = "radio")]
+    relationship: Relationship,
     is_adult: bool,
+    hobbies: Vec<String>,
+    #[admin(readonly, sortable)]
+    created_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct MyModelInput {
-    name: String,
-    is_adult: bool,
+#[derive(Clone, Debug, Serialize, Deserialize, AdminSelect)]
+#[serde(rename_all = "snake_case")]
+enum Relationship {
+    Friend,
+    Family,
+    #[admin(label = "Work colleague")]
+    Colleague,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, AdminResource)]
+#[admin(path = "gifts", label = "description", create_input = "GiftInput")]
+struct Gift {
+    #[admin(readonly, sortable)]
+    id: i32,
+    #[admin(searchable, required)]
+    description: String,
+    #[admin(relation = "model")]
+    recipient_id: i32,
+    #[admin(relation = "model")]
+    shared_with: Vec<i32>,
+}
+
+// Models can live in other modules, and are registered with the admin from wherever they're
+// visible
+mod address {
+    use razer_core_derive::AdminResource;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Serialize, Deserialize, Debug, AdminResource)]
+    #[admin(path = "addresses", label = "street", create_input = "AddressInput")]
+    pub struct Address {
+        #[admin(readonly)]
+        pub id: i32,
+        #[admin(relation = "model")]
+        pub person_id: i32,
+        #[admin(searchable, required)]
+        pub street: String,
+        pub city: String,
+    }
+}
+
+/// Keeps the models in memory, in the admin context
+struct MyModelDataSource;
+
+impl AdminDataSource<AdminContext> for MyModelDataSource {
+    type Id = i32;
+    type Item = MyModel;
+    type CreateInput = MyModelInput;
+    type UpdateInput = MyModelInput;
+
+    async fn list(
+        &self,
+        context: AdminContext,
+        query: ListQuery,
+    ) -> Result<ListPage<MyModel>, AdminError> {
+        let mut models = context.my_models.lock().await.clone();
+        models.retain(|item| {
+            let value = serde_json::to_value(item).unwrap();
+            query.filters.iter().all(|filter| filter.matches(&value))
+                && query
+                    .search
+                    .as_ref()
+                    .is_none_or(|search| search.matches(&value))
+        });
+        if let Some(sort) = &query.sort {
+            match sort.field_id.as_str() {
+                "id" => models.sort_by_key(|item| item.id),
+                "name" => models.sort_by(|a, b| a.name.cmp(&b.name)),
+                "created_at" => models.sort_by_key(|item| item.created_at),
+                _ => {}
+            }
+            if sort.direction == SortDirection::Descending {
+                models.reverse();
+            }
+        }
+
+        Ok(ListPage {
+            items: models
+                .iter()
+                .skip(query.offset() as usize)
+                .take(query.page_size as usize)
+                .cloned()
+                .collect(),
+            total: Some(models.len() as u64),
+            next_cursor: None,
+        })
+    }
+
+    async fn count(&self, context: AdminContext) -> Result<Option<u64>, AdminError> {
+        Ok(Some(context.my_models.lock().await.len() as u64))
+    }
+
+    async fn get(&self, context: AdminContext, id: i32) -> Result<MyModel, AdminError> {
+        let models = context.my_models.lock().await;
+        let item = models.iter().find(|item| item.id == id);
+        match item {
+            Some(value) => Ok(value.clone()),
+            None => Err(AdminError::NotFound),
+        }
+    }
+
+    async fn create(
+        &self,
+        context: AdminContext,
+        data: MyModelInput,
+    ) -> Result<MyModel, AdminError> {
+        let mut models = context.my_models.lock().await;
+        let next_id = models
+            .iter()
+            .max_by_key(|item| item.id)
+            .map_or(0, |it| it.id + 1);
+
+        let item = data.into_item(next_id, Utc::now());
+        models.push(item.clone());
+
+        Ok(item)
+    }
+
+    async fn update(
+        &self,
+        context: AdminContext,
+        id: i32,
+        data: MyModelInput,
+    ) -> Result<MyModel, AdminError> {
+        let mut models = context.my_models.lock().await;
+        let item = models.iter_mut().find(|item| item.id == id);
+        match item {
+            Some(item) => {
+                data.apply_to(item);
+                Ok(item.clone())
+            }
+            None => Err(AdminError::NotFound),
+        }
+    }
+
+    async fn delete(&self, context: AdminContext, id: i32) -> Result<(), AdminError> {
+        let mut models = context.my_models.lock().await;
+        let index = models.iter().position(|item| item.id == id);
+        match index {
+            Some(index) => {
+                models.remove(index);
+                Ok(())
+            }
+            None => Err(AdminError::NotFound),
+        }
+    }
+
+    async fn validate_create(
+        &self,
+        context: AdminContext,
+        input: &MyModelInput,
+    ) -> Result<(), ValidationErrors> {
+        let models = context.my_models.lock().await;
+        let mut errors = ValidationErrors::default();
+        if models.iter().any(|model| model.name == input.name) {
+            errors.add_field_error("name", "Name must be unique");
+        }
+
+        errors.into_result()
+    }
+
+    async fn validate_update(
+        &self,
+        context: AdminContext,
+        id: &i32,
+        input: &MyModelInput,
+    ) -> Result<(), ValidationErrors> {
+        let models = context.my_models.lock().await;
+        let mut errors = ValidationErrors::default();
+        if models
+            .iter()
+            .any(|model| model.id != *id && model.name == input.name)
+        {
+            errors.add_field_error("name", "Name must be unique");
+        }
+
+        errors.into_result()
+    }
+}
+
+/// Keeps the gifts in memory, in the admin context
+struct GiftDataSource;
+
+impl AdminDataSource<AdminContext> for GiftDataSource {
+    type Id = i32;
+    type Item = Gift;
+    type CreateInput = GiftInput;
+    type UpdateInput = GiftInput;
+
+    async fn list(
+        &self,
+        context: AdminContext,
+        query: ListQuery,
+    ) -> Result<ListPage<Gift>, AdminError> {
+        let mut gifts = context.gifts.lock().await.clone();
+        gifts.retain(|item| {
+            let value = serde_json::to_value(item).unwrap();
+            query.filters.iter().all(|filter| filter.matches(&value))
+                && query
+                    .search
+                    .as_ref()
+                    .is_none_or(|search| search.matches(&value))
+        });
+        if query
+            .sort
+            .as_ref()
+            .is_some_and(|sort| sort.direction == SortDirection::Descending)
+        {
+            gifts.reverse();
+        }
+
+        Ok(ListPage {
+            items: gifts
+                .iter()
+                .skip(query.offset() as usize)
+                .take(query.page_size as usize)
+                .cloned()
+                .collect(),
+            total: Some(gifts.len() as u64),
+            next_cursor: None,
+        })
+    }
+
+    async fn count(&self, context: AdminContext) -> Result<Option<u64>, AdminError> {
+        Ok(Some(context.gifts.lock().await.len() as u64))
+    }
+
+    async fn get(&self, context: AdminContext, id: i32) -> Result<Gift, AdminError> {
+        let gifts = context.gifts.lock().await;
+        gifts
+            .iter()
+            .find(|item| item.id == id)
+            .cloned()
+            .ok_or(AdminError::NotFound)
+    }
+
+    async fn create(&self, context: AdminContext, data: GiftInput) -> Result<Gift, AdminError> {
+        let mut gifts = context.gifts.lock().await;
+        let next_id = gifts.iter().map(|item| item.id + 1).max().unwrap_or(0);
+
+        let item = data.into_item(next_id);
+        gifts.push(item.clone());
+
+        Ok(item)
+    }
+
+    async fn update(
+        &self,
+        context: AdminContext,
+        id: i32,
+        data: GiftInput,
+    ) -> Result<Gift, AdminError> {
+        let mut gifts = context.gifts.lock().await;
+        let item = gifts
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or(AdminError::NotFound)?;
+        data.apply_to(item);
+        Ok(item.clone())
+    }
+
+    async fn delete(&self, context: AdminContext, id: i32) -> Result<(), AdminError> {
+        let mut gifts = context.gifts.lock().await;
+        let index = gifts
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or(AdminError::NotFound)?;
+        gifts.remove(index);
+        Ok(())
+    }
+}
+
+/// Keeps the addresses in memory, in the admin context. They're nested under the models, so lists
+/// always have a filter on `person_id` and new addresses always have it set.
+struct AddressDataSource;
+
+impl AdminDataSource<AdminContext> for AddressDataSource {
+    type Id = i32;
+    type Item = Address;
+    type CreateInput = AddressInput;
+    type UpdateInput = AddressInput;
+
+    async fn list(
+        &self,
+        context: AdminContext,
+        query: ListQuery,
+    ) -> Result<ListPage<Address>, AdminError> {
+        let mut addresses = context.addresses.lock().await.clone();
+        addresses.retain(|item| {
+            let value = serde_json::to_value(item).unwrap();
+            query.filters.iter().all(|filter| filter.matches(&value))
+                && query
+                    .search
+                    .as_ref()
+                    .is_none_or(|search| search.matches(&value))
+        });
+
+        Ok(ListPage {
+            items: addresses
+                .iter()
+                .skip(query.offset() as usize)
+                .take(query.page_size as usize)
+                .cloned()
+                .collect(),
+            total: Some(addresses.len() as u64),
+            next_cursor: None,
+        })
+    }
+
+    async fn get(&self, context: AdminContext, id: i32) -> Result<Address, AdminError> {
+        let addresses = context.addresses.lock().await;
+        addresses
+            .iter()
+            .find(|item| item.id == id)
+            .cloned()
+            .ok_or(AdminError::NotFound)
+    }
+
+    async fn create(
+        &self,
+        context: AdminContext,
+        data: AddressInput,
+    ) -> Result<Address, AdminError> {
+        let mut addresses = context.addresses.lock().await;
+        let next_id = addresses.iter().map(|item| item.id + 1).max().unwrap_or(0);
+
+        let item = data.into_item(next_id);
+        addresses.push(item.clone());
+
+        Ok(item)
+    }
+
+    async fn update(
+        &self,
+        context: AdminContext,
+        id: i32,
+        data: AddressInput,
+    ) -> Result<Address, AdminError> {
+        let mut addresses = context.addresses.lock().await;
+        let item = addresses
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or(AdminError::NotFound)?;
+        data.apply_to(item);
+        Ok(item.clone())
+    }
+
+    async fn delete(&self, context: AdminContext, id: i32) -> Result<(), AdminError> {
+        let mut addresses = context.addresses.lock().await;
+        let index = addresses
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or(AdminError::NotFound)?;
+        addresses.remove(index);
+        Ok(())
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    let resource = Resource::<AdminContext, i32, MyModel, MyModelInput> {
-        name: "My Model".to_string(),
-        path: "model".to_string(),
-        id_field_id: "id".to_string(),
-
-        list_items: Arc::new(|context| {
-            Box::pin(async move {
-                let models = context.my_models.lock().await;
-                Ok(models.iter().cloned().collect())
-            })
-        }),
-
-        get_item: Arc::new(|context, id| {
-            Box::pin(async move {
-                let models = context.my_models.lock().await;
-                let item = models.iter().find(|item| item.id == id);
-                match item {
-                    Some(value) => Ok(value.clone()),
-                    None => Err(AdminError::NotFound),
-                }
-            })
-        }),
-
-        create_item: Arc::new(|context, data| {
-            Box::pin(async move {
-                let next_id = {
-                    let models = context.my_models.lock().await;
-                    models.iter().max_by_key(|item| item.id).map_or(0, |it| it.id+ 1)
-                };
-
-                let item = MyModel {
-                    id: next_id,
-                    is_adult: data.is_adult,
-                    name: data.name,
-                };
-
-                let mut models = context.my_models.lock().await;
-                models.push(item.clone());
-
-                Ok(item)
-            })
-        }),
-        // field_configs: MyModel::default_field_configs(),
-        // field_configs: MyModel::field_configs().into(),
-        field_configs: MyModelFieldConfigs {
-            is_adult: FieldConfig {
-                render: Arc::new(move |value| {
-                    Ok(render_toggle_widget(
-                        MyModel::field_configs().is_adult.field_id,
-                        MyModel::field_configs().is_adult.display_name,
-                        value,
-                    ))
-                }),
-                ..MyModel::field_configs().is_adult
-            },
-            ..MyModel::field_configs()
-        }.into(),
-
-        // field_configs: vec![
-        //     FieldConfig::create_number_config("id".to_string(), "Id".to_string(), true),
-        //     FieldConfig::create_text_config("name".to_string(), "Name".to_string(), false),
-        //     FieldConfig::create_boolean_config("is_adult".to_string(), "Is adult".to_string(), false),
-        //     // FieldConfig {
-        //     //     field_id: "is_adult".to_string(),
-        //     //     display_name: "Is an adult?".to_string(),
-        //     //     help_text: None,
-        //     //     description: None,
-        //     //     render: Arc::new(move |value| {
-        //     //         // TODO Create functions to render with return type string inside razer_ui e.g.
-        //     //         // render_toggle
-        //     //         Ok(render_toggle_widget(
-        //     //             "is_adult".to_string(),
-        //     //             "Is an adult?".to_string(),
-        //     //             value,
-        //     //         ))
-        //     //     }),
-        //     //     validate: Arc::new(|value| ValidationResult::Valid),
-        //     // },
-        // ],
-    };
-
     let admin = Admin::new(AdminContext {
         my_models: Arc::new(Mutex::new(vec![MyModel {
             id: 1,
             name: "Susan".to_string(),
+            nickname: None,
+            height: 1.68,
+            birthday: NaiveDate::from_ymd_opt(1990, 4, 12),
+            relationship: Relationship::Friend,
             is_adult: true,
+            hobbies: vec!["Hiking".to_string()],
+            created_at: Utc::now(),
+        }])),
+        gifts: Arc::new(Mutex::new(vec![Gift {
+            id: 1,
+            description: "Hiking boots".to_string(),
+            recipient_id: 1,
+            shared_with: vec![],
+        }])),
+        addresses: Arc::new(Mutex::new(vec![Address {
+            id: 1,
+            person_id: 1,
+            street: "12 High Street".to_string(),
+            city: "Bristol".to_string(),
         }])),
     })
-    .register(resource);
+    .register(
+        MyModel::admin_resource(MyModelDataSource)
+            .with_field_configs(
+                MyModelFieldConfigs {
+                    is_adult: FieldConfig {
+                        render: Arc::new(move |value, constraints| {
+                            Ok(render_toggle_widget(
+                                MyModel::field_configs().is_adult.field_id,
+                                MyModel::field_configs().is_adult.display_name,
+                                value,
+                                constraints.html_attributes(),
+                            ))
+                        }),
+                        ..MyModel::field_configs().is_adult
+                    },
+                    ..MyModel::field_configs()
+                }
+                .into(),
+            )
+            .with_related_list(RelatedList::new("gifts", "recipient_id")),
+    )
+    .register(Gift::admin_resource(GiftDataSource))
+    .register_nested(
+        "model",
+        "person_id",
+        Address::admin_resource(AddressDataSource),
+    )
+    .with_dashboard_widget(|context: AdminContext| {
+        Box::pin(async move {
+            let models = context.my_models.lock().await;
+            let adults = models.iter().filter(|model| model.is_adult).count();
+
+            Ok(render_stat_tile(
+                "Adults".to_string(),
+                adults.to_string(),
+                Some("/admin/model?is_adult__eq=true".to_string()),
+            ))
+        })
+    })
+    .with_dashboard_widget(|context: AdminContext| {
+        Box::pin(async move {
+            let gifts = context.gifts.lock().await;
+
+            Ok(render_recent_items(
+                "Latest gifts".to_string(),
+                gifts
+                    .iter()
+                    .rev()
+                    .take(5)
+                    .map(|gift| {
+                        (
+                            gift.description.clone(),
+                            format!("/admin/gifts/{}", gift.id),
+                        )
+                    })
+                    .collect(),
+            ))
+        })
+    });
 
     let app = Router::new().nest("/admin", razer_axum::AxumRouter(admin).into());
 
@@ -1,16 +1,36 @@
 use askama::Template;
 use axum::{
-    extract::{Path, RawForm},
-    http::{header, HeaderMap, StatusCode},
-    response::IntoResponse,
+    extract::{Form, Path, Query, RawForm, RawQuery},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Response},
     routing::{get, post},
     Router,
 };
-use razer_core::Admin;
+use razer_core::{
+    id_from_json, json_from_form_data, Admin, AdminError, FieldConfig, FilterCondition,
+    FilterConfig, JsonResource, JsonResourceItem, ListQuery, RelatedList, ValidationErrors,
+};
 use razer_ui::{
-    get_default_template_css, render_create_resource_view, render_list_resource_view,
-    render_not_found_view, render_view_resource_view, AdminListTemplateRow,
+    get_default_template_css, render_admin_home, render_bulk_delete_result,
+    render_create_resource_view, render_display_chips, render_display_link, render_display_value,
+    render_edit_resource_view, render_empty_value, render_error_fragment, render_error_tile,
+    render_error_view, render_form, render_form_field, render_list_resource_view,
+    render_list_rows_fragment, render_related_list, render_relation_label, render_relation_options,
+    render_view_resource_view, AdminBreadcrumb, AdminChip, AdminForm, AdminFormField,
+    AdminListFilter, AdminListFilterInput, AdminListFilters, AdminListPagination, AdminListSearch,
+    AdminListTemplateCell, AdminListTemplateHeader, AdminListTemplateRow, AdminRelatedList,
+    AdminResourceCard, EMPTY_VALUE_PLACEHOLDER,
 };
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Arc};
+
+// How many matches a relation field's search shows
+const AUTOCOMPLETE_PAGE_SIZE: u64 = 10;
+// How many related items are shown at a time on a detail page
+const RELATED_LIST_PAGE_SIZE: u64 = 10;
+
+// Every registered resource by path, for looking up the items of relation fields and related lists
+type Relations<TContext> = HashMap<String, JsonResource<TContext>>;
 
 pub struct HtmlTemplate<T>(T);
 
@@ -27,150 +47,1326 @@ impl<T: Template> IntoResponse for HtmlTemplate<T> {
     }
 }
 
-pub struct AxumRouter<TContext>(pub Admin<TContext>);
+/// Converts an item's id value into the string used in urls, without the quotes json strings would
+/// be rendered with
+fn item_id_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
 
-impl<TContext: Send + Sync + Clone + 'static> Into<Router> for AxumRouter<TContext> {
-    fn into(self) -> Router {
-        self.0
-            .resources
-            .into_iter()
-            .fold(Router::new(), |router, resource| {
-                let list_url = format!("/admin/{}", resource.path);
-                let context = self.0.context.clone();
-
-                let get_view_route = {
-                    let resource_name = resource.name.clone();
-                    let field_configs = resource.field_configs.clone();
-                    let context = context.clone();
-
-                    |Path(id): Path<String>| async move {
-                        // TODO no unwrap
-                        let value = (*resource.get_item)(context, id).await;
-
-                        let html = match value {
-                            Ok(value) => render_view_resource_view(
-                                resource_name.clone(),
-                                field_configs
-                                    .iter()
-                                    .map(|field| {
-                                        let field_value = value.get(&field.field_id).unwrap();
-                                        // TODO Handle
-                                        (field.render)(field_value.clone()).unwrap()
-                                    })
-                                    .collect(),
-                            ),
-                            Err(razer_core::AdminError::NotFound) => render_not_found_view(),
-                            Err(razer_core::AdminError::InternalError) => {
-                                todo!()
-                            }
-                        };
+/// Fetches the labels of the items the relation fields of some items point at, keyed by field id
+/// and related item id. Each related item is fetched once, and items which can't be fetched are
+/// left out so they're shown by id.
+async fn relation_labels<'a, TContext: Clone>(
+    context: &TContext,
+    relations: &Relations<TContext>,
+    field_configs: &[FieldConfig],
+    items: impl IntoIterator<Item = &'a JsonResourceItem>,
+) -> HashMap<(String, String), String> {
+    let mut labels = HashMap::new();
+
+    for item in items {
+        for config in field_configs {
+            let Some(relation) = &config.relation else {
+                continue;
+            };
+            let Some(related) = relations.get(&relation.resource_path) else {
+                continue;
+            };
+            // Fields relating to many items hold a list of ids
+            let ids: Vec<String> = match item.get(&config.field_id) {
+                Some(serde_json::Value::Array(values)) => {
+                    values.iter().filter_map(id_from_json).collect()
+                }
+                value => value.and_then(id_from_json).into_iter().collect(),
+            };
+
+            for id in ids {
+                let key = (config.field_id.clone(), id.clone());
+                if labels.contains_key(&key) {
+                    continue;
+                }
+
+                if let Ok(related_item) = (*related.get_item)(context.clone(), id).await {
+                    labels.insert(key, related.item_label(&related_item));
+                }
+            }
+        }
+    }
+
+    labels
+}
+
+/// The chips the values of a list field are shown as. Relation fields are shown by the labels of
+/// the items they point at, see [`relation_labels`].
+fn chips(
+    config: &FieldConfig,
+    values: &[serde_json::Value],
+    labels: &HashMap<(String, String), String>,
+) -> Vec<AdminChip> {
+    values
+        .iter()
+        .map(|value| match (&config.relation, &config.display) {
+            (Some(relation), _) => {
+                let related_id = id_from_json(value).unwrap_or_default();
+                AdminChip {
+                    label: labels
+                        .get(&(config.field_id.clone(), related_id.clone()))
+                        .cloned()
+                        .unwrap_or_else(|| related_id.clone()),
+                    link: Some(relation.item_url(&related_id)),
+                }
+            }
+            (None, Some(display)) => AdminChip {
+                label: display(value),
+                link: None,
+            },
+            (None, None) => AdminChip {
+                label: id_from_json(value).unwrap_or_default(),
+                link: None,
+            },
+        })
+        .collect()
+}
+
+/// Builds a row of a list of items. Relation fields are shown by the labels of the items they
+/// point at, see [`relation_labels`].
+fn list_row<'a>(
+    list_url: &str,
+    id_field_id: &str,
+    field_configs: impl IntoIterator<Item = &'a FieldConfig>,
+    item: &JsonResourceItem,
+    labels: &HashMap<(String, String), String>,
+) -> Result<AdminListTemplateRow, AdminError> {
+    // Without an id there's no way to link to or delete the item
+    let item_id = item_id_to_string(item.get(id_field_id).ok_or(AdminError::InternalError)?);
 
-                        axum::response::Html(html).into_response()
+    Ok(AdminListTemplateRow {
+        item_link: format!("{}/{}", list_url, item_id),
+        item_id,
+        data: field_configs
+            .into_iter()
+            // TODO Render more than just strings - have render function for list view?
+            .map(
+                |config| match (item.get(&config.field_id), &config.relation) {
+                    (None | Some(serde_json::Value::Null), _) => AdminListTemplateCell {
+                        value: EMPTY_VALUE_PLACEHOLDER.to_string(),
+                        link: None,
+                        chips: vec![],
+                    },
+                    (Some(serde_json::Value::Array(values)), _) if config.many => {
+                        AdminListTemplateCell {
+                            value: EMPTY_VALUE_PLACEHOLDER.to_string(),
+                            link: None,
+                            chips: chips(config, values, labels),
+                        }
                     }
-                };
+                    (Some(value), Some(relation)) => {
+                        let related_id = id_from_json(value).unwrap_or_default();
+                        AdminListTemplateCell {
+                            value: labels
+                                .get(&(config.field_id.clone(), related_id.clone()))
+                                .cloned()
+                                .unwrap_or_else(|| related_id.clone()),
+                            link: Some(relation.item_url(&related_id)),
+                            chips: vec![],
+                        }
+                    }
+                    (Some(value), None) => AdminListTemplateCell {
+                        value: config.display_value(value),
+                        link: None,
+                        chips: vec![],
+                    },
+                },
+            )
+            .collect(),
+    })
+}
 
-                let list_view_route = {
-                    let resource_name = resource.name.clone();
-                    let resource_path = resource.path.clone();
-                    let field_configs = resource.field_configs.clone();
-                    let headers = resource
-                        .field_configs
-                        .iter()
-                        .map(|config| config.display_name.clone())
-                        .collect();
-                    let context = context.clone();
-
-                    || async move {
-                        let items = (*resource.list_items)(context).await;
-
-                        let html = match items {
-                            Ok(items) => {
-                                let values = items
-                                    .iter()
-                                    .map(|item| {
-                                        let item_id = item.get(&resource.id_field_id).unwrap().clone();
-
-                                        AdminListTemplateRow {
-                                            item_link: format!("/admin/{}/{}", resource_path, item_id),
-                                            data: field_configs
-                                                .iter()
-                                                // TODO Render more than just strings - have render
-                                                // function for list view?
-                                                .map(|config| {
-                                                    item.get(&config.field_id).unwrap().to_string()
-                                                })
-                                                .collect(),
-                                        }
-                                    })
-                                    .collect();
-
-                                render_list_resource_view(
-                                    resource_name.clone(),
-                                    // TODO Get base url from somewhere
-                                    format!("/admin/{}/create", resource_path),
-                                    headers,
-                                    values,
-                                )
-                            }
-                            Err(_) => todo!(),
-                        };
+/// Builds the filter sidebar for the list view, prefilled with the filters currently applied
+fn list_filters(
+    list_url: String,
+    field_configs: &[FieldConfig],
+    list_query: &ListQuery,
+) -> AdminListFilters {
+    let filters = field_configs
+        .iter()
+        .filter_map(|config| {
+            let conditions = list_query
+                .filters
+                .iter()
+                .filter(|filter| filter.field_id == config.field_id)
+                .map(|filter| &filter.condition);
 
-                        axum::response::Html(html).into_response()
+            let input = match config.filter_config? {
+                FilterConfig::Boolean => AdminListFilterInput::Boolean {
+                    value: conditions
+                        .filter_map(|condition| match condition {
+                            FilterCondition::Equals(value) => Some(*value),
+                            _ => None,
+                        })
+                        .next(),
+                },
+                FilterConfig::NumberRange => {
+                    let range = conditions
+                        .filter_map(|condition| match condition {
+                            FilterCondition::Range { min, max } => Some((min, max)),
+                            _ => None,
+                        })
+                        .next();
+
+                    AdminListFilterInput::NumberRange {
+                        min: range.and_then(|(min, _)| min.as_ref().map(|min| min.to_string())),
+                        max: range.and_then(|(_, max)| max.as_ref().map(|max| max.to_string())),
                     }
-                };
+                }
+                FilterConfig::Text => {
+                    let (mut contains, mut starts_with) = (None, None);
+                    for condition in conditions {
+                        match condition {
+                            FilterCondition::Contains(value) => contains = Some(value.clone()),
+                            FilterCondition::StartsWith(value) => starts_with = Some(value.clone()),
+                            _ => {}
+                        }
+                    }
+
+                    AdminListFilterInput::Text {
+                        contains,
+                        starts_with,
+                    }
+                }
+                FilterConfig::Relation => AdminListFilterInput::Relation {
+                    id: conditions
+                        .filter_map(|condition| match condition {
+                            FilterCondition::Id(id) => Some(id.clone()),
+                            _ => None,
+                        })
+                        .next(),
+                },
+            };
+
+            Some(AdminListFilter {
+                field_id: config.field_id.clone(),
+                display_name: config.display_name.clone(),
+                input,
+            })
+        })
+        .collect();
 
-                let create_view_route = {
-                    let resource_path = resource.path.clone();
+    // The filter form replaces the filters and starts again from the first page, everything else
+    // about the current query is kept
+    let hidden_params = ListQuery {
+        page: 1,
+        cursor: None,
+        filters: vec![],
+        ..list_query.clone()
+    }
+    .to_query_pairs();
 
-                    || async move {
-                        // TODO This should not be in razer package!!
-                        let html = render_create_resource_view(
+    // Likewise the search form only replaces the search term
+    let search = field_configs
+        .iter()
+        .any(|config| config.searchable)
+        .then(|| AdminListSearch {
+            term: list_query
+                .search
+                .as_ref()
+                .map(|search| search.term.clone())
+                .unwrap_or_default(),
+            hidden_params: ListQuery {
+                page: 1,
+                cursor: None,
+                search: None,
+                ..list_query.clone()
+            }
+            .to_query_pairs(),
+        });
+
+    AdminListFilters {
+        list_endpoint: list_url,
+        filters,
+        hidden_params,
+        search,
+    }
+}
+
+/// Whether the request was made by htmx, rather than being a full page load
+fn is_htmx_request(request_headers: &HeaderMap) -> bool {
+    request_headers.contains_key("HX-Request")
+}
+
+fn error_status(error: &AdminError) -> StatusCode {
+    match error {
+        AdminError::NotFound => StatusCode::NOT_FOUND,
+        AdminError::InternalError | AdminError::Custom { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        AdminError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        AdminError::Unauthorized => StatusCode::UNAUTHORIZED,
+        AdminError::Forbidden => StatusCode::FORBIDDEN,
+        AdminError::Conflict(_) => StatusCode::CONFLICT,
+        AdminError::BadRequest(_) => StatusCode::BAD_REQUEST,
+    }
+}
+
+/// The title and messages an error is shown to the user with
+fn error_title_and_messages(error: &AdminError) -> (String, Vec<String>) {
+    match error {
+        AdminError::NotFound => (
+            "Not found".to_string(),
+            vec!["The requested item could not be found.".to_string()],
+        ),
+        AdminError::InternalError => ("Something went wrong".to_string(), vec![]),
+        AdminError::Validation(errors) => {
+            let mut field_errors: Vec<_> = errors.field_errors.iter().collect();
+            field_errors.sort();
+
+            (
+                "Invalid input".to_string(),
+                errors
+                    .form_errors
+                    .iter()
+                    .cloned()
+                    .chain(
+                        field_errors
+                            .into_iter()
+                            .map(|(field_id, message)| format!("{}: {}", field_id, message)),
+                    )
+                    .collect(),
+            )
+        }
+        AdminError::Unauthorized => (
+            "Unauthorized".to_string(),
+            vec!["You need to sign in to do this.".to_string()],
+        ),
+        AdminError::Forbidden => (
+            "Forbidden".to_string(),
+            vec!["You don't have permission to do this.".to_string()],
+        ),
+        AdminError::Conflict(message) => ("Conflict".to_string(), vec![message.clone()]),
+        AdminError::BadRequest(message) => ("Bad request".to_string(), vec![message.clone()]),
+        AdminError::Custom { message, .. } => {
+            ("Something went wrong".to_string(), vec![message.clone()])
+        }
+    }
+}
+
+/// Renders an error with its status code. Full page loads get an error page while htmx requests
+/// get an alert, retargeted to the error area of the current page so it never replaces the
+/// element the request was made from.
+fn error_response(error: AdminError, request_headers: &HeaderMap) -> Response {
+    let status = error_status(&error);
+    let (title, messages) = error_title_and_messages(&error);
+
+    if is_htmx_request(request_headers) {
+        let mut headers = HeaderMap::new();
+        headers.insert("HX-Retarget", HeaderValue::from_static("#admin-error"));
+        headers.insert("HX-Reswap", HeaderValue::from_static("innerHTML"));
+
+        return (
+            status,
+            headers,
+            Html(render_error_fragment(title, messages)),
+        )
+            .into_response();
+    }
+
+    (status, Html(render_error_view(title, messages))).into_response()
+}
+
+/// Redirects the browser once an htmx request has succeeded
+fn redirect_response(status: StatusCode, url: &str) -> Response {
+    match HeaderValue::from_str(url) {
+        Ok(url) => (status, [("HX-Redirect", url)]).into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// Builds a single form field, prefilled with its value and any validation error
+fn form_field(
+    validate_url: &str,
+    field_config: &FieldConfig,
+    values: &JsonResourceItem,
+    errors: &ValidationErrors,
+) -> Result<AdminFormField, AdminError> {
+    let field_value = values
+        .get(&field_config.field_id)
+        .cloned()
+        .unwrap_or(serde_json::Value::Null);
+    let read_only = field_config.create_config.is_none();
+
+    Ok(AdminFormField {
+        field_id: field_config.field_id.clone(),
+        html: field_config.render_widget(field_value)?,
+        read_only,
+        error: errors.field_error(&field_config.field_id).cloned(),
+        validate_endpoint: (!read_only)
+            .then(|| format!("{}/{}", validate_url, field_config.field_id)),
+    })
+}
+
+/// Builds the create or edit form for the given fields
+fn form<'a>(
+    endpoint: String,
+    submit_label: &str,
+    validate_url: &str,
+    field_configs: impl IntoIterator<Item = &'a FieldConfig>,
+    values: &JsonResourceItem,
+    errors: &ValidationErrors,
+) -> Result<AdminForm, AdminError> {
+    let fields = field_configs
+        .into_iter()
+        .map(|field_config| form_field(validate_url, field_config, values, errors))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Errors against fields which aren't part of the form would otherwise never be seen, so they
+    // are shown with the errors for the whole form
+    let mut unplaced_errors: Vec<(&String, &String)> = errors
+        .field_errors
+        .iter()
+        .filter(|(field_id, _)| fields.iter().all(|field| &field.field_id != *field_id))
+        .collect();
+    unplaced_errors.sort();
+
+    Ok(AdminForm {
+        endpoint,
+        submit_label: submit_label.to_string(),
+        fields,
+        errors: errors
+            .form_errors
+            .iter()
+            .cloned()
+            .chain(
+                unplaced_errors
+                    .into_iter()
+                    .map(|(_, message)| message.clone()),
+            )
+            .collect(),
+    })
+}
+
+// Nested resources are served under their parent's item routes, which already name the parent
+// item's id `id`, so their own items' ids are named `nested_id`
+
+/// The path of a resource's list routes, e.g. the list and create views
+#[derive(Deserialize)]
+struct ListPath {
+    // The parent item's id, for nested resources
+    id: Option<String>,
+}
+
+/// The path of a resource's item routes, e.g. the detail and edit views
+#[derive(Deserialize)]
+struct ItemPath {
+    id: String,
+    nested_id: Option<String>,
+}
+
+impl ItemPath {
+    /// The parent item's id, for nested resources, and the item's id
+    fn into_ids(self) -> (Option<String>, String) {
+        match self.nested_id {
+            Some(nested_id) => (Some(self.id), nested_id),
+            None => (None, self.id),
+        }
+    }
+}
+
+/// The path of a resource's field routes, i.e. validating a single field
+#[derive(Deserialize)]
+struct FieldPath {
+    // The parent item's id, for nested resources
+    id: Option<String>,
+    field_id: String,
+}
+
+/// The resource as served for a request, along with the breadcrumbs leading to its list view.
+/// Nested resources are served for the parent item in the request's path, which must exist, see
+/// [`JsonResource::for_parent`].
+async fn scoped_resource<TContext: Clone + Send + 'static>(
+    context: &TContext,
+    relations: &Relations<TContext>,
+    resource: &JsonResource<TContext>,
+    parent_id: Option<&str>,
+) -> Result<(JsonResource<TContext>, Vec<AdminBreadcrumb>), AdminError> {
+    let resource_breadcrumb = AdminBreadcrumb {
+        label: resource.name.clone(),
+        link: Some(resource.list_url(parent_id)),
+    };
+    let (Some(parent), Some(parent_id)) = (&resource.parent, parent_id) else {
+        return Ok((resource.clone(), vec![resource_breadcrumb]));
+    };
+
+    let parent_resource = relations
+        .get(&parent.resource_path)
+        .ok_or(AdminError::NotFound)?;
+    let parent_item = (*parent_resource.get_item)(context.clone(), parent_id.to_string()).await?;
+
+    Ok((
+        resource.for_parent(parent_id),
+        vec![
+            AdminBreadcrumb {
+                label: parent_resource.name.clone(),
+                link: Some(parent_resource.list_url(None)),
+            },
+            AdminBreadcrumb {
+                label: parent_resource.item_label(&parent_item),
+                link: parent_resource.item_url(&parent_item),
+            },
+            resource_breadcrumb,
+        ],
+    ))
+}
+
+/// Builds the routes of a single resource, to be nested under the resource's list url
+fn resource_router<TContext: Send + Sync + Clone + 'static>(
+    resource: JsonResource<TContext>,
+    relations: Arc<Relations<TContext>>,
+    context: TContext,
+) -> Router {
+    let edit_view_route =
+        {
+            let resource = resource.clone();
+            let relations = relations.clone();
+            let context = context.clone();
+
+            |request_headers: HeaderMap, Path(path): Path<ItemPath>| async move {
+                let (parent_id, id) = path.into_ids();
+                let (resource, mut breadcrumbs) =
+                    match scoped_resource(&context, &relations, &resource, parent_id.as_deref())
+                        .await
+                    {
+                        Ok(scoped) => scoped,
+                        Err(e) => return error_response(e, &request_headers),
+                    };
+                let list_url = resource.list_url(parent_id.as_deref());
+
+                let html = (*resource.get_item)(context, id.clone())
+                    .await
+                    .and_then(|value| {
+                        breadcrumbs.push(AdminBreadcrumb {
+                            label: resource.item_label(&value),
+                            link: Some(format!("{}/{}", list_url, id)),
+                        });
+                        breadcrumbs.push(AdminBreadcrumb {
+                            label: "Edit".to_string(),
+                            link: None,
+                        });
+
+                        Ok(render_edit_resource_view(
                             resource.name.clone(),
-                            // TODO Get base url from somewhere
-                            format!("/admin/{}/create", resource_path),
-                            resource
-                                .field_configs
-                                .iter()
-                                .filter(|field_config| field_config.create_config.is_some())
-                                .map(|field| {
-                                    // TODO Don't unwrap - render error page
-                                    (field.render)(serde_json::Value::Null).unwrap()
+                            breadcrumbs,
+                            form(
+                                format!("{}/{}/edit", list_url, id),
+                                "Save",
+                                &format!("{}/validate", list_url),
+                                &resource.field_configs,
+                                &value,
+                                &ValidationErrors::default(),
+                            )?,
+                        ))
+                    });
+
+                match html {
+                    Ok(html) => Html(html).into_response(),
+                    Err(e) => error_response(e, &request_headers),
+                }
+            }
+        };
+
+    let get_view_route = {
+        let resource = resource.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap, Path(path): Path<ItemPath>| async move {
+            let (parent_id, id) = path.into_ids();
+            let (resource, mut breadcrumbs) = match scoped_resource(
+                &context,
+                &relations,
+                &resource,
+                parent_id.as_deref(),
+            )
+            .await
+            {
+                Ok(scoped) => scoped,
+                Err(e) => return error_response(e, &request_headers),
+            };
+            let list_url = resource.list_url(parent_id.as_deref());
+            let field_configs = &resource.field_configs;
+
+            let value = (*resource.get_item)(context.clone(), id.clone()).await;
+            let labels = match &value {
+                Ok(value) => relation_labels(&context, &relations, field_configs, [value]).await,
+                Err(_) => HashMap::new(),
+            };
+
+            let html = value.and_then(|value| {
+                let field_widgets = field_configs
+                    .iter()
+                    .map(
+                        |field| match (value.get(&field.field_id), &field.relation) {
+                            (None | Some(serde_json::Value::Null), _) => {
+                                Ok(render_empty_value(field.display_name.clone()))
+                            }
+                            (Some(serde_json::Value::Array(values)), _) if field.many => {
+                                Ok(if values.is_empty() {
+                                    render_empty_value(field.display_name.clone())
+                                } else {
+                                    render_display_chips(
+                                        field.display_name.clone(),
+                                        chips(field, values, &labels),
+                                    )
                                 })
-                                .collect(),
-                        );
+                            }
+                            (Some(field_value), Some(relation)) => {
+                                let related_id = id_from_json(field_value).unwrap_or_default();
+                                Ok(render_display_link(
+                                    field.display_name.clone(),
+                                    labels
+                                        .get(&(field.field_id.clone(), related_id.clone()))
+                                        .cloned()
+                                        .unwrap_or_else(|| related_id.clone()),
+                                    relation.item_url(&related_id),
+                                ))
+                            }
+                            (Some(field_value), None) if field.display.is_some() => {
+                                Ok(render_display_value(
+                                    field.display_name.clone(),
+                                    field.display_value(field_value),
+                                ))
+                            }
+                            (Some(field_value), None) => field.render_widget(field_value.clone()),
+                        },
+                    )
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                breadcrumbs.push(AdminBreadcrumb {
+                    label: resource.item_label(&value),
+                    link: None,
+                });
+
+                Ok(render_view_resource_view(
+                    resource.name.clone(),
+                    breadcrumbs,
+                    format!("{}/{}/edit", list_url, id),
+                    format!("{}/{}/delete", list_url, id),
+                    field_widgets,
+                    resource
+                        .related_lists
+                        .iter()
+                        .map(|related_list| related_list.section_url(&resource.path, &id))
+                        .collect(),
+                ))
+            });
 
-                        axum::response::Html(html).into_response()
+            match html {
+                Ok(html) => Html(html).into_response(),
+                Err(e) => error_response(e, &request_headers),
+            }
+        }
+    };
+
+    let list_view_route = {
+        let resource = resource.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap, Path(path): Path<ListPath>, RawQuery(query): RawQuery| async move {
+            let (resource, mut breadcrumbs) =
+                match scoped_resource(&context, &relations, &resource, path.id.as_deref()).await {
+                    Ok(scoped) => scoped,
+                    Err(e) => return error_response(e, &request_headers),
+                };
+            let list_url = resource.list_url(path.id.as_deref());
+            let field_configs = &resource.field_configs;
+
+            // Searching swaps in only the table rows rather than reloading the page
+            let is_rows_request = request_headers
+                .get("HX-Target")
+                .is_some_and(|target| target == "list-rows");
+
+            let mut list_query = ListQuery::from_query_string(query.as_deref().unwrap_or(""));
+            // Never pass a sort or filter through to the data source for a field which doesn't
+            // support it
+            list_query.retain_supported(field_configs);
+            let list_page = match (*resource.list_items)(context.clone(), list_query.clone()).await
+            {
+                Ok(list_page) => list_page,
+                Err(e) => return error_response(e, &request_headers),
+            };
+            let labels =
+                relation_labels(&context, &relations, field_configs, &list_page.items).await;
+
+            let headers = field_configs
+                .iter()
+                .map(|config| AdminListTemplateHeader {
+                    display_name: config.display_name.clone(),
+                    sort_link: config.sortable.then(|| {
+                        format!(
+                            "{}?{}",
+                            list_url,
+                            list_query.sorted_by(&config.field_id).to_query_string()
+                        )
+                    }),
+                    sort_direction: list_query
+                        .sort
+                        .as_ref()
+                        .filter(|sort| sort.field_id == config.field_id)
+                        .map(|sort| sort.direction.as_str().to_string()),
+                })
+                .collect();
+            let pagination = AdminListPagination {
+                page: list_query.page,
+                page_count: list_page
+                    .total
+                    .map(|total| total.div_ceil(list_query.page_size).max(1)),
+                total: list_page.total,
+                previous_link: list_query
+                    .previous_page()
+                    .map(|query| format!("{}?{}", list_url, query.to_query_string())),
+                next_link: list_query
+                    .next_page(&list_page)
+                    .map(|query| format!("{}?{}", list_url, query.to_query_string())),
+            };
+
+            let rows = list_page
+                .items
+                .iter()
+                .map(|item| {
+                    list_row(
+                        &list_url,
+                        &resource.id_field_id,
+                        field_configs,
+                        item,
+                        &labels,
+                    )
+                })
+                .collect::<Result<Vec<_>, AdminError>>();
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(e) => return error_response(e, &request_headers),
+            };
+
+            if is_rows_request {
+                return Html(render_list_rows_fragment(rows, pagination)).into_response();
+            }
+
+            // The list is the current page
+            if let Some(breadcrumb) = breadcrumbs.last_mut() {
+                breadcrumb.link = None;
+            }
+
+            Html(render_list_resource_view(
+                resource.name.clone(),
+                breadcrumbs,
+                resource.description.clone(),
+                format!("{}/create", list_url),
+                format!("{}/delete", list_url),
+                headers,
+                rows,
+                pagination,
+                list_filters(list_url, field_configs, &list_query),
+            ))
+            .into_response()
+        }
+    };
+
+    let create_view_route = {
+        let resource = resource.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap,
+         Path(path): Path<ListPath>,
+         Query(params): Query<HashMap<String, String>>| async move {
+            let (resource, mut breadcrumbs) =
+                match scoped_resource(&context, &relations, &resource, path.id.as_deref()).await {
+                    Ok(scoped) => scoped,
+                    Err(e) => return error_response(e, &request_headers),
+                };
+            let list_url = resource.list_url(path.id.as_deref());
+            breadcrumbs.push(AdminBreadcrumb {
+                label: "Create".to_string(),
+                link: None,
+            });
+
+            // Fields can be prefilled from the query string, e.g. the relation field of an item
+            // created from a related list
+            let values: JsonResourceItem = resource
+                .field_configs
+                .iter()
+                .filter_map(|field_config| {
+                    let value = params.get(&field_config.field_id)?;
+                    let create_config = field_config.create_config.as_ref()?;
+                    Some((
+                        field_config.field_id.clone(),
+                        (create_config.value_from_form_value)(Some(value)),
+                    ))
+                })
+                .collect();
+            // TODO This should not be in razer package!!
+            let form = form(
+                format!("{}/create", list_url),
+                "Create",
+                &format!("{}/validate", list_url),
+                resource
+                    .field_configs
+                    .iter()
+                    .filter(|field_config| field_config.is_creatable()),
+                &values,
+                &ValidationErrors::default(),
+            );
+
+            match form {
+                Ok(form) => Html(render_create_resource_view(
+                    resource.name.clone(),
+                    breadcrumbs,
+                    form,
+                ))
+                .into_response(),
+                Err(e) => error_response(e, &request_headers),
+            }
+        }
+    };
+
+    let create_api_route = {
+        let resource = resource.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap, Path(path): Path<ListPath>, RawForm(bytes): RawForm| async move {
+            let (resource, _) =
+                match scoped_resource(&context, &relations, &resource, path.id.as_deref()).await {
+                    Ok(scoped) => scoped,
+                    Err(e) => return error_response(e, &request_headers),
+                };
+            let list_url = resource.list_url(path.id.as_deref());
+
+            match (*resource.create_item)(context, &bytes).await {
+                Ok(()) => redirect_response(StatusCode::CREATED, &list_url),
+                // Send the form back with the submitted values and the errors in place
+                Err(AdminError::Validation(errors)) => {
+                    let form = form(
+                        format!("{}/create", list_url),
+                        "Create",
+                        &format!("{}/validate", list_url),
+                        resource
+                            .field_configs
+                            .iter()
+                            .filter(|field_config| field_config.is_creatable()),
+                        &json_from_form_data(&resource.field_configs, &bytes),
+                        &errors,
+                    );
+
+                    match form {
+                        Ok(form) => (StatusCode::UNPROCESSABLE_ENTITY, Html(render_form(form)))
+                            .into_response(),
+                        Err(e) => error_response(e, &request_headers),
                     }
+                }
+                Err(e) => error_response(e, &request_headers),
+            }
+        }
+    };
+
+    let edit_api_route = {
+        let resource = resource.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap, Path(path): Path<ItemPath>, RawForm(bytes): RawForm| async move {
+            let (parent_id, id) = path.into_ids();
+            let (resource, _) = match scoped_resource(
+                &context,
+                &relations,
+                &resource,
+                parent_id.as_deref(),
+            )
+            .await
+            {
+                Ok(scoped) => scoped,
+                Err(e) => return error_response(e, &request_headers),
+            };
+            let list_url = resource.list_url(parent_id.as_deref());
+
+            let updated_item = (*resource.update_item)(context.clone(), id.clone(), &bytes).await;
+
+            match updated_item {
+                Ok(()) => redirect_response(StatusCode::OK, &format!("{}/{}", list_url, id)),
+                Err(AdminError::Validation(errors)) => {
+                    // Read only fields aren't submitted so keep showing their stored values
+                    // alongside the submitted ones
+                    let mut values = match (*resource.get_item)(context, id.clone()).await {
+                        Ok(values) => values,
+                        Err(e) => return error_response(e, &request_headers),
+                    };
+                    values.extend(json_from_form_data(&resource.field_configs, &bytes));
+
+                    let form = form(
+                        format!("{}/{}/edit", list_url, id),
+                        "Save",
+                        &format!("{}/validate", list_url),
+                        &resource.field_configs,
+                        &values,
+                        &errors,
+                    );
+
+                    match form {
+                        Ok(form) => (StatusCode::UNPROCESSABLE_ENTITY, Html(render_form(form)))
+                            .into_response(),
+                        Err(e) => error_response(e, &request_headers),
+                    }
+                }
+                Err(e) => error_response(e, &request_headers),
+            }
+        }
+    };
+
+    // Validates a single field as it loses focus, returning just that field
+    let validate_field_api_route = {
+        let resource = resource.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap, Path(path): Path<FieldPath>, RawForm(bytes): RawForm| async move {
+            // Nested resources are validated as they're submitted, without the parent's field
+            let (resource, _) =
+                match scoped_resource(&context, &relations, &resource, path.id.as_deref()).await {
+                    Ok(scoped) => scoped,
+                    Err(e) => return error_response(e, &request_headers),
                 };
+            let field_configs = &resource.field_configs;
+            let field_id = path.field_id;
+            let Some(field_config) = field_configs
+                .iter()
+                .find(|field_config| field_config.field_id == field_id)
+            else {
+                return error_response(AdminError::NotFound, &request_headers);
+            };
 
-                let create_api_route = {
-                    let context = context.clone();
+            let values = json_from_form_data(field_configs, &bytes);
+            let mut errors = ValidationErrors::default();
+            if let Some(message) = field_config.validation_error(
+                values
+                    .get(&field_id)
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null),
+            ) {
+                errors.add_field_error(field_id.clone(), message);
+            }
 
-                    // |Form(input): Form<serde_json::Map<String, serde_json::Value>>| async move {
-                    |RawForm(bytes): RawForm| async move {
-                        let mut headers = HeaderMap::new();
+            let validate_url = format!("{}/validate", resource.list_url(path.id.as_deref()));
+            match form_field(&validate_url, field_config, &values, &errors) {
+                Ok(field) => Html(render_form_field(field)).into_response(),
+                Err(e) => error_response(e, &request_headers),
+            }
+        }
+    };
 
-                        let created_item = (*resource.create_item)(context, &bytes).await;
-                        dbg!(created_item);
+    let delete_api_route =
+        {
+            let resource = resource.clone();
+            let relations = relations.clone();
+            let context = context.clone();
 
-                        headers.insert("HX-Redirect", list_url.parse().unwrap());
+            |request_headers: HeaderMap, Path(path): Path<ItemPath>| async move {
+                let (parent_id, id) = path.into_ids();
+                let (resource, _) =
+                    match scoped_resource(&context, &relations, &resource, parent_id.as_deref())
+                        .await
+                    {
+                        Ok(scoped) => scoped,
+                        Err(e) => return error_response(e, &request_headers),
+                    };
 
-                        (
-                            StatusCode::CREATED,
-                            headers,
-                            // axum::extract::Json(output)
-                        )
+                match (*resource.delete_item)(context, id).await {
+                    Ok(()) => {
+                        redirect_response(StatusCode::OK, &resource.list_url(parent_id.as_deref()))
                     }
+                    Err(e) => error_response(e, &request_headers),
+                }
+            }
+        };
+
+    let bulk_delete_api_route = {
+        let resource = resource.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap,
+         Path(path): Path<ListPath>,
+         Form(form): Form<Vec<(String, String)>>| async move {
+            let (resource, _) =
+                match scoped_resource(&context, &relations, &resource, path.id.as_deref()).await {
+                    Ok(scoped) => scoped,
+                    Err(e) => return error_response(e, &request_headers),
                 };
+            let ids = form
+                .into_iter()
+                .filter(|(key, _)| key == "ids")
+                .map(|(_, id)| id)
+                .collect();
+
+            let result = (*resource.delete_items)(context, ids).await;
+
+            Html(render_bulk_delete_result(
+                result.deleted,
+                result.failed.len(),
+            ))
+            .into_response()
+        }
+    };
+
+    let router = Router::new()
+        .route("/create", get(create_view_route))
+        .route("/create", post(create_api_route))
+        .route("/", get(list_view_route))
+        .route("/delete", post(bulk_delete_api_route))
+        .route("/validate/{field_id}", post(validate_field_api_route));
+
+    // Nested resources can't be related to, so have no autocomplete, labels or related lists of
+    // their own. See the checks in `From<AxumRouter>`.
+    if resource.parent.is_some() {
+        return router
+            .route("/{nested_id}", get(get_view_route))
+            .route("/{nested_id}/edit", get(edit_view_route))
+            .route("/{nested_id}/edit", post(edit_api_route))
+            .route("/{nested_id}/delete", post(delete_api_route));
+    }
+
+    // One page of the items of another resource which relate to an item, loaded into the item's
+    // detail page
+    let related_list_api_route = {
+        let resource_path = resource.path.clone();
+        let related_lists = resource.related_lists.clone();
+        let relations = relations.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap,
+         Path((id, related_path, field_id)): Path<(String, String, String)>,
+         RawQuery(query): RawQuery| async move {
+            let related_list = related_lists.iter().find(|related_list| {
+                related_list.resource_path == related_path && related_list.field_id == field_id
+            });
+            let (Some(related_list), Some(related)) = (related_list, relations.get(&related_path))
+            else {
+                return error_response(AdminError::NotFound, &request_headers);
+            };
+
+            let list_query = ListQuery {
+                page: ListQuery::from_query_string(query.as_deref().unwrap_or("")).page,
+                page_size: RELATED_LIST_PAGE_SIZE,
+                ..related_list.list_query(&id)
+            };
+            let list_page = match (*related.list_items)(context.clone(), list_query.clone()).await {
+                Ok(list_page) => list_page,
+                Err(e) => return error_response(e, &request_headers),
+            };
+
+            // Every item relates to the one being viewed, so its relation field is left out
+            let field_configs: Vec<&FieldConfig> = related
+                .field_configs
+                .iter()
+                .filter(|config| config.field_id != field_id)
+                .collect();
+            let labels = relation_labels(
+                &context,
+                &relations,
+                &related.field_configs,
+                &list_page.items,
+            )
+            .await;
+
+            // Resources nested under this one are served under the item being viewed
+            let nested_list_url = related
+                .parent
+                .as_ref()
+                .filter(|parent| parent.field_id == field_id)
+                .map(|_| related.list_url(Some(&id)));
+            let related_list_url = nested_list_url
+                .clone()
+                .unwrap_or_else(|| related.list_url(None));
+
+            let rows = list_page
+                .items
+                .iter()
+                .map(|item| {
+                    list_row(
+                        &related_list_url,
+                        &related.id_field_id,
+                        field_configs.iter().copied(),
+                        item,
+                        &labels,
+                    )
+                })
+                .collect::<Result<Vec<_>, AdminError>>();
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(e) => return error_response(e, &request_headers),
+            };
+
+            let section_url = related_list.section_url(&resource_path, &id);
+            let page_link = |query: ListQuery| format!("{}?page={}", section_url, query.page);
+            let pagination = AdminListPagination {
+                page: list_query.page,
+                page_count: list_page
+                    .total
+                    .map(|total| total.div_ceil(list_query.page_size).max(1)),
+                total: list_page.total,
+                previous_link: list_query.previous_page().map(page_link),
+                next_link: list_query.next_page(&list_page).map(page_link),
+            };
+
+            let (create_link, list_link) = match nested_list_url {
+                Some(list_url) => (format!("{}/create", list_url), list_url),
+                None => (related_list.create_url(&id), related_list.list_url(&id)),
+            };
+
+            Html(render_related_list(AdminRelatedList {
+                title: related.name.clone(),
+                create_link,
+                list_link,
+                headers: field_configs
+                    .iter()
+                    .map(|config| config.display_name.clone())
+                    .collect(),
+                rows,
+                pagination,
+            }))
+            .into_response()
+        }
+    };
+
+    // Searches the resource for the relation fields of other resources which point at it,
+    // returning the matches as options to pick from
+    let autocomplete_api_route = {
+        let resource = resource.clone();
+        let context = context.clone();
+
+        |request_headers: HeaderMap, RawQuery(query): RawQuery| async move {
+            let mut list_query = ListQuery::from_query_string(query.as_deref().unwrap_or(""));
+            list_query.retain_supported(&resource.field_configs);
+            list_query.page_size = AUTOCOMPLETE_PAGE_SIZE;
+
+            let list_page = match (*resource.list_items)(context, list_query).await {
+                Ok(list_page) => list_page,
+                Err(e) => return error_response(e, &request_headers),
+            };
+
+            let options = list_page
+                .items
+                .iter()
+                .filter_map(|item| Some((resource.item_id(item)?, resource.item_label(item))))
+                .collect();
+
+            Html(render_relation_options(options)).into_response()
+        }
+    };
 
-                let nested_router = Router::new()
-                    .route("/create", get(create_view_route))
-                    .route("/create", post(create_api_route))
-                    .route("/", get(list_view_route))
-                    .route("/{id}", get(get_view_route));
+    // The label an item is shown with by the relation fields pointing at it
+    let label_api_route = {
+        let resource = resource.clone();
+        let context = context.clone();
 
-                router.nest(format!("/{}", resource.path).as_str(), nested_router)
+        |Path(id): Path<String>| async move {
+            // A relation to an item which no longer exists still shows its id
+            let label = match (*resource.get_item)(context, id.clone()).await {
+                Ok(item) => resource.item_label(&item),
+                Err(_) => id,
+            };
+
+            Html(render_relation_label(label))
+        }
+    };
+
+    router
+        .route("/autocomplete", get(autocomplete_api_route))
+        .route("/{id}", get(get_view_route))
+        .route("/{id}/label", get(label_api_route))
+        .route(
+            "/{id}/related/{related_path}/{field_id}",
+            get(related_list_api_route),
+        )
+        .route("/{id}/edit", get(edit_view_route))
+        .route("/{id}/edit", post(edit_api_route))
+        .route("/{id}/delete", post(delete_api_route))
+}
+
+pub struct AxumRouter<TContext>(pub Admin<TContext>);
+
+impl<TContext: Send + Sync + Clone + 'static> From<AxumRouter<TContext>> for Router {
+    fn from(value: AxumRouter<TContext>) -> Self {
+        let admin = value.0;
+        let mut resources = admin.resources;
+
+        for resource in &resources {
+            assert!(
+                resources
+                    .iter()
+                    .filter(|other| other.path == resource.path)
+                    .count()
+                    == 1,
+                "`{}` is the path of more than one resource",
+                resource.path
+            );
+
+            let Some(parent) = &resource.parent else {
+                continue;
+            };
+            let parent_resource = resources
+                .iter()
+                .find(|other| other.path == parent.resource_path);
+            assert!(
+                parent_resource.is_some_and(|parent_resource| parent_resource.parent.is_none()),
+                "`{}` is nested under `{}`, which isn't a registered resource that isn't nested \
+                 itself",
+                resource.name,
+                parent.resource_path
+            );
+            assert!(
+                resource
+                    .field_configs
+                    .iter()
+                    .find(|config| config.field_id == parent.field_id)
+                    .and_then(|config| config.relation.as_ref())
+                    .is_some_and(|relation| relation.resource_path == parent.resource_path),
+                "`{}` is nested under `{}` by `{}`, which isn't a relation field pointing at it",
+                resource.name,
+                parent.resource_path,
+                parent.field_id
+            );
+        }
+
+        // Nested resources are listed on the detail pages of their parent items
+        let nested_lists: Vec<(String, RelatedList)> = resources
+            .iter()
+            .filter_map(|resource| {
+                let parent = resource.parent.as_ref()?;
+                Some((
+                    parent.resource_path.clone(),
+                    RelatedList::new(resource.path.clone(), parent.field_id.clone()),
+                ))
             })
+            .collect();
+        for (parent_path, related_list) in nested_lists {
+            if let Some(parent) = resources
+                .iter_mut()
+                .find(|resource| resource.path == parent_path)
+            {
+                if !parent.related_lists.contains(&related_list) {
+                    parent.related_lists.push(related_list);
+                }
+            }
+        }
+
+        let relations: Arc<Relations<TContext>> = Arc::new(
+            resources
+                .iter()
+                .map(|resource| (resource.path.clone(), resource.clone()))
+                .collect(),
+        );
+        // A relation to a resource which isn't registered can never be shown, so catch it here
+        // rather than on every request
+        for resource in &resources {
+            for config in &resource.field_configs {
+                if let Some(relation) = &config.relation {
+                    let related = relations.get(&relation.resource_path);
+                    assert!(
+                        related.is_some(),
+                        "`{}` of `{}` is a relation to `{}`, which isn't a registered resource",
+                        config.field_id,
+                        resource.name,
+                        relation.resource_path
+                    );
+                    assert!(
+                        related.is_some_and(|related| related.parent.is_none()),
+                        "`{}` of `{}` is a relation to `{}`, which is nested so is only served \
+                         under its parent",
+                        config.field_id,
+                        resource.name,
+                        relation.resource_path
+                    );
+                }
+            }
+            for related_list in &resource.related_lists {
+                let points_back = relations
+                    .get(&related_list.resource_path)
+                    .and_then(|related| {
+                        related
+                            .field_configs
+                            .iter()
+                            .find(|config| config.field_id == related_list.field_id)
+                    })
+                    .and_then(|config| config.relation.as_ref())
+                    .is_some_and(|relation| relation.resource_path == resource.path);
+                assert!(
+                    points_back,
+                    "`{}` lists `{}` by `{}`, which isn't a relation field of a registered \
+                     resource pointing back at `{}`",
+                    resource.name, related_list.resource_path, related_list.field_id, resource.path
+                );
+            }
+        }
+
+        // The home page, with a card for each resource and the dashboard widgets
+        let home_route = {
+            // Nested resources are only listed under their parent items
+            let resources: Vec<JsonResource<TContext>> = resources
+                .iter()
+                .filter(|resource| resource.parent.is_none())
+                .cloned()
+                .collect();
+            let widgets = admin.widgets.clone();
+            let title = admin.title.clone();
+            let context = admin.context.clone();
+
+            || async move {
+                let mut cards = vec![];
+                for resource in &resources {
+                    // A count which fails is left out rather than failing the whole page
+                    let count = (*resource.count_items)(context.clone())
+                        .await
+                        .ok()
+                        .flatten();
+                    cards.push(AdminResourceCard {
+                        name: resource.name.clone(),
+                        description: resource.description.clone(),
+                        link: resource.list_url(None),
+                        count,
+                    });
+                }
+
+                // Likewise a widget which fails is replaced by a tile showing its error
+                let mut widget_html = vec![];
+                for widget in &widgets {
+                    widget_html.push(match (*widget)(context.clone()).await {
+                        Ok(html) => html,
+                        Err(e) => {
+                            let (title, messages) = error_title_and_messages(&e);
+                            render_error_tile(title, messages)
+                        }
+                    });
+                }
+
+                Html(render_admin_home(title, cards, widget_html))
+            }
+        };
+
+        resources
+            .into_iter()
+            .fold(Router::new(), |router, resource| {
+                let path = match &resource.parent {
+                    Some(parent) => format!("/{}/{{id}}/{}", parent.resource_path, resource.path),
+                    None => format!("/{}", resource.path),
+                };
+
+                router.nest(
+                    &path,
+                    resource_router(resource, relations.clone(), admin.context.clone()),
+                )
+            })
+            .route("/", get(home_route))
             .route(
                 "/assets/admin.css",
                 get(async || {
@@ -183,3 +1379,398 @@ impl<TContext: Send + Sync + Clone + 'static> Into<Router> for AxumRouter<TConte
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::{body::Body, http::Request};
+    use razer_core::{AdminDataSource, ListPage};
+    use razer_core_derive::AdminResource;
+    use razer_ui::render_stat_tile;
+    use serde::Serialize;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[derive(Clone, Serialize, Deserialize, AdminResource)]
+    #[admin(path = "people", label = "name", create_input = "PersonInput")]
+    struct Person {
+        #[admin(readonly)]
+        id: u32,
+        #[admin(searchable, required)]
+        name: String,
+    }
+
+    #[derive(Clone, Serialize, Deserialize, AdminResource)]
+    #[admin(path = "addresses", label = "street", create_input = "AddressInput")]
+    struct Address {
+        #[admin(readonly)]
+        id: u32,
+        #[admin(relation = "people")]
+        person_id: u32,
+        #[admin(required)]
+        street: String,
+    }
+
+    // Filters, searches and pages the items as a data source would
+    fn list_page<T: Serialize + Clone>(items: &[T], query: &ListQuery) -> ListPage<T> {
+        let items: Vec<T> = items
+            .iter()
+            .filter(|item| {
+                let value = serde_json::to_value(item).unwrap();
+                query.filters.iter().all(|filter| filter.matches(&value))
+                    && query
+                        .search
+                        .as_ref()
+                        .is_none_or(|search| search.matches(&value))
+            })
+            .cloned()
+            .collect();
+
+        ListPage {
+            total: Some(items.len() as u64),
+            items: items
+                .into_iter()
+                .skip(query.offset() as usize)
+                .take(query.page_size as usize)
+                .collect(),
+            next_cursor: None,
+        }
+    }
+
+    /// Keeps the people in memory, along with their addresses so they can't be deleted while they
+    /// have any
+    struct People {
+        people: Arc<Mutex<Vec<Person>>>,
+        addresses: Arc<Mutex<Vec<Address>>>,
+    }
+
+    impl AdminDataSource<()> for People {
+        type Id = u32;
+        type Item = Person;
+        type CreateInput = PersonInput;
+        type UpdateInput = PersonInput;
+
+        async fn list(&self, _: (), query: ListQuery) -> Result<ListPage<Person>, AdminError> {
+            Ok(list_page(&self.people.lock().unwrap(), &query))
+        }
+
+        async fn get(&self, _: (), id: u32) -> Result<Person, AdminError> {
+            if id == 0 {
+                return Err(AdminError::BadRequest("Ids start at 1".to_string()));
+            }
+
+            let people = self.people.lock().unwrap();
+            people
+                .iter()
+                .find(|person| person.id == id)
+                .cloned()
+                .ok_or(AdminError::NotFound)
+        }
+
+        async fn create(&self, _: (), input: PersonInput) -> Result<Person, AdminError> {
+            let mut people = self.people.lock().unwrap();
+            if people.iter().any(|person| person.name == input.name) {
+                return Err(AdminError::Conflict(format!(
+                    "{} has already been added",
+                    input.name
+                )));
+            }
+
+            let person = input.into_item(people.len() as u32 + 1);
+            people.push(person.clone());
+            Ok(person)
+        }
+
+        async fn update(&self, _: (), id: u32, input: PersonInput) -> Result<Person, AdminError> {
+            let mut people = self.people.lock().unwrap();
+            let person = people
+                .iter_mut()
+                .find(|person| person.id == id)
+                .ok_or(AdminError::NotFound)?;
+            input.apply_to(person);
+            Ok(person.clone())
+        }
+
+        async fn delete(&self, _: (), id: u32) -> Result<(), AdminError> {
+            let addresses = self.addresses.lock().unwrap();
+            if addresses.iter().any(|address| address.person_id == id) {
+                let mut errors = ValidationErrors::default();
+                errors
+                    .form_errors
+                    .push("Delete their addresses first".to_string());
+                return Err(AdminError::Validation(errors));
+            }
+
+            self.people.lock().unwrap().retain(|person| person.id != id);
+            Ok(())
+        }
+    }
+
+    /// Keeps the addresses in memory, nested under the people
+    struct Addresses(Arc<Mutex<Vec<Address>>>);
+
+    impl AdminDataSource<()> for Addresses {
+        type Id = u32;
+        type Item = Address;
+        type CreateInput = AddressInput;
+        type UpdateInput = AddressInput;
+
+        async fn list(&self, _: (), query: ListQuery) -> Result<ListPage<Address>, AdminError> {
+            Ok(list_page(&self.0.lock().unwrap(), &query))
+        }
+
+        async fn get(&self, _: (), id: u32) -> Result<Address, AdminError> {
+            let addresses = self.0.lock().unwrap();
+            addresses
+                .iter()
+                .find(|address| address.id == id)
+                .cloned()
+                .ok_or(AdminError::NotFound)
+        }
+
+        async fn create(&self, _: (), input: AddressInput) -> Result<Address, AdminError> {
+            let mut addresses = self.0.lock().unwrap();
+            let address = input.into_item(addresses.len() as u32 + 1);
+            addresses.push(address.clone());
+            Ok(address)
+        }
+
+        async fn update(&self, _: (), id: u32, input: AddressInput) -> Result<Address, AdminError> {
+            let mut addresses = self.0.lock().unwrap();
+            let address = addresses
+                .iter_mut()
+                .find(|address| address.id == id)
+                .ok_or(AdminError::NotFound)?;
+            input.apply_to(address);
+            Ok(address.clone())
+        }
+
+        async fn delete(&self, _: (), id: u32) -> Result<(), AdminError> {
+            self.0.lock().unwrap().retain(|address| address.id != id);
+            Ok(())
+        }
+    }
+
+    fn admin() -> Admin<()> {
+        let people = vec![
+            Person {
+                id: 1,
+                name: "Ada".to_string(),
+            },
+            Person {
+                id: 2,
+                name: "Grace".to_string(),
+            },
+            Person {
+                id: 3,
+                name: "Alan".to_string(),
+            },
+            Person {
+                id: 4,
+                name: "Adele".to_string(),
+            },
+        ];
+        let addresses = Arc::new(Mutex::new(vec![Address {
+            id: 1,
+            person_id: 1,
+            street: "12 High Street".to_string(),
+        }]));
+
+        Admin::new(())
+            .register(Person::admin_resource(People {
+                people: Arc::new(Mutex::new(people)),
+                addresses: addresses.clone(),
+            }))
+            .register_nested(
+                "people",
+                "person_id",
+                Address::admin_resource(Addresses(addresses)),
+            )
+    }
+
+    // Sends a request to the admin, served under `/admin` as in an app
+    async fn send(admin: Admin<()>, request: Request<Body>) -> (StatusCode, HeaderMap, String) {
+        let response = Router::new()
+            .nest("/admin", AxumRouter(admin).into())
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, headers, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn form_request(uri: &str, body: &str) -> Request<Body> {
+        Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header("HX-Request", "true")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn nested_fields_are_validated_for_their_parent() {
+        let (status, _, body) = send(
+            admin(),
+            form_request("/admin/people/1/addresses/validate/street", "street="),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("This field is required"));
+
+        // The parent's field is hidden from nested forms and set from the path when submitted
+        let (status, _, _) = send(
+            admin(),
+            form_request(
+                "/admin/people/1/addresses/validate/person_id",
+                "person_id=2",
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, _) = send(
+            admin(),
+            form_request("/admin/people/9/addresses/validate/street", "street="),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn htmx_errors_are_retargeted_with_their_status() {
+        let (status, headers, body) =
+            send(admin(), form_request("/admin/people/create", "name=Ada")).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(headers["HX-Retarget"], "#admin-error");
+        assert_eq!(headers["HX-Reswap"], "innerHTML");
+        assert!(body.contains("Ada has already been added"));
+        assert!(!body.contains("<html"));
+
+        let (status, headers, body) =
+            send(admin(), form_request("/admin/people/1/delete", "")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(headers["HX-Retarget"], "#admin-error");
+        assert!(body.contains("Delete their addresses first"));
+
+        // Validation errors for a submitted form come back as the form rather than an alert
+        let (status, headers, body) =
+            send(admin(), form_request("/admin/people/create", "name=")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(!headers.contains_key("HX-Retarget"));
+        assert!(body.contains("This field is required"));
+    }
+
+    #[tokio::test]
+    async fn page_loads_get_an_error_page_with_their_status() {
+        let (status, headers, body) = send(
+            admin(),
+            Request::get("/admin/people/0").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(!headers.contains_key("HX-Retarget"));
+        assert!(body.contains("<html"));
+        assert!(body.contains("Ids start at 1"));
+
+        let (status, _, body) = send(
+            admin(),
+            Request::get("/admin/people/9").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body.contains("<html"));
+    }
+
+    fn rows_request(uri: &str) -> Request<Body> {
+        Request::get(uri)
+            .header("HX-Request", "true")
+            .header("HX-Target", "list-rows")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn searching_returns_only_the_rows() {
+        let (status, _, body) = send(admin(), rows_request("/admin/people?q=gr")).await;
+        assert_eq!(status, StatusCode::OK);
+        // Row ids are the hex encoded item ids
+        assert!(body.contains(r#"id="row-32""#));
+        assert!(!body.contains(r#"id="row-31""#));
+        assert!(body.contains(r#"hx-swap-oob="true""#));
+        assert!(!body.contains("<html"));
+
+        // Anything else loading the list gets the whole page
+        let (status, _, body) = send(
+            admin(),
+            Request::get("/admin/people?q=gr")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(r#"id="row-32""#));
+        assert!(!body.contains(r#"id="row-31""#));
+        assert!(body.contains("<html"));
+    }
+
+    #[tokio::test]
+    async fn searching_keeps_the_filters_and_pages_the_results() {
+        let (_, _, body) = send(admin(), rows_request("/admin/people?q=ad&id__gte=2")).await;
+        assert!(body.contains(r#"id="row-34""#));
+        assert!(!body.contains(r#"id="row-31""#));
+        assert!(body.contains("(1 items)"));
+
+        let (_, _, body) = send(
+            admin(),
+            rows_request("/admin/people?q=a&page_size=1&page=2"),
+        )
+        .await;
+        assert!(body.contains(r#"id="row-32""#));
+        assert!(!body.contains(r#"id="row-31""#));
+        assert!(body.contains("Page 2 of 4"));
+        // The search is carried over to the other pages
+        assert!(body.contains("/admin/people?page=3&amp;page_size=1&amp;q=a"));
+        assert!(body.contains("/admin/people?page=1&amp;page_size=1&amp;q=a"));
+    }
+
+    #[tokio::test]
+    async fn failing_widgets_do_not_fail_the_home_page() {
+        let admin = admin()
+            .with_dashboard_widget(|_| {
+                Box::pin(async {
+                    Ok(render_stat_tile(
+                        "People".to_string(),
+                        "4".to_string(),
+                        None,
+                    ))
+                })
+            })
+            .with_dashboard_widget(|_| {
+                Box::pin(async {
+                    Err(AdminError::Conflict(
+                        "Orders are being imported".to_string(),
+                    ))
+                })
+            });
+
+        let (status, _, body) =
+            send(admin, Request::get("/admin").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(&render_stat_tile(
+            "People".to_string(),
+            "4".to_string(),
+            None
+        )));
+        assert!(body.contains(&render_error_tile(
+            "Conflict".to_string(),
+            vec!["Orders are being imported".to_string()]
+        )));
+    }
+}
@@ -1,46 +1,45 @@
-use std::fmt;
-
-use proc_macro::{Span, TokenStream};
-use quote::quote;
-use syn::{punctuated::Punctuated, DeriveInput, FieldsNamed, Ident, Meta, Token};
+use proc_macro::TokenStream;
+use quote::{ToTokens, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{DeriveInput, FieldsNamed, Ident, Meta, Token, punctuated::Punctuated};
 
+/// The `#[admin(...)]` arguments on the struct itself
+#[derive(Default)]
 struct AdminResourceDeriveArgs {
-    name: String,
-    description: String,
+    name: Option<String>,
+    create_input: Option<syn::Ident>,
+    path: Option<String>,
+    id: Option<syn::LitStr>,
+    label: Option<syn::LitStr>,
+    description: Option<String>,
 }
 
-#[derive(Debug, Clone)]
-struct CompileError {
-    msg: String,
-    span: Option<Span>,
+fn parse_string_literal(name_value: &syn::MetaNameValue) -> syn::Result<syn::LitStr> {
+    match &name_value.value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(value),
+            ..
+        }) => Ok(value.clone()),
+        value => Err(syn::Error::new_spanned(
+            value,
+            format!(
+                "expected `{}` to be a string literal",
+                name_value.path.to_token_stream()
+            ),
+        )),
+    }
 }
 
-impl CompileError {
-    fn new(message: &str) -> Self {
-        Self {
-            msg: message.to_string(),
-            span: None,
-        }
-    }
+fn find_admin_attr(attrs: &[syn::Attribute]) -> Option<&syn::Attribute> {
+    attrs.iter().find(|attr| attr.path().is_ident("admin"))
 }
 
-impl std::error::Error for CompileError {}
-impl fmt::Display for CompileError {
-    #[inline]
-    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt.write_str(&self.msg)
-    }
+fn parse_admin_args(attr: &syn::Attribute) -> syn::Result<Punctuated<Meta, Token![,]>> {
+    attr.parse_args_with(<Punctuated<Meta, Token![,]>>::parse_terminated)
 }
 
-/// ```
-/// assert_eq!(razer::attribute_name_to_display_name("attr_name"), "Attr name");
-/// ```
-/// ```
-/// assert_eq!(razer::attribute_name_to_display_name("hello"), "Hello");
-/// ```
-/// ```
-/// assert_eq!(razer::attribute_name_to_display_name("abc_def_hi"), "Abc def hi");
-/// ```
+/// Converts a snake case field id into a human readable display name, e.g. `abc_def_hi` becomes
+/// `Abc def hi`.
 fn field_id_to_display_name(name: &str) -> String {
     // Replace underscores with spaces
     let name = name.replace("_", " ");
@@ -53,145 +52,568 @@ fn field_id_to_display_name(name: &str) -> String {
     }
 }
 
+/// Converts a camel case struct name into snake case, e.g. `MyModel` becomes `my_model`
+fn ident_to_snake_case(name: &str) -> String {
+    name.chars()
+        .enumerate()
+        .fold(String::new(), |mut snake_case, (index, c)| {
+            if c.is_uppercase() && index > 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(c.to_lowercase());
+            snake_case
+        })
+}
+
 #[proc_macro_derive(AdminResource, attributes(admin))]
 pub fn derive_admin_resource(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as DeriveInput);
-    // TODO No unwrap
-    derive_admin_resource_impl(input).unwrap()
+    derive_admin_resource_impl(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Lists the variants of a unit-only enum as select options, and implements `AdminField` so it can
+/// be used in models. Labels are worked out from the variant names unless given with
+/// `#[admin(label = "...")]`.
+#[proc_macro_derive(AdminSelect, attributes(admin))]
+pub fn derive_admin_select(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    derive_admin_select_impl(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 struct AdminFieldData {
     name: syn::Ident,
     ty: syn::Type,
+    vis: syn::Visibility,
+    // Copied onto the generated create input so it (de)serializes the same way as the model,
+    // apart from renames, see `serde_attrs_without_renames`
+    serde_attrs: Vec<syn::Attribute>,
     is_readonly: bool,
+    is_skip_create: bool,
+    is_sortable: bool,
+    is_searchable: bool,
+    // `format = "..."`, the format dates and times are shown in
+    display_format: Option<syn::LitStr>,
+    // `widget = "..."`, for types which can be shown with more than one widget, as the name of
+    // its marker type in `razer_core::widget`
+    widget: Option<syn::Ident>,
+    // `relation = "..."`, the path of the resource the field holds the id of
+    relation: Option<syn::LitStr>,
+    constraints: FieldConstraintsData,
 }
 
-enum FieldType {
-    String,
-    Number,
-    Boolean,
+/// The validation attributes given for a field, e.g. `#[admin(required, length(max = 64))]`
+#[derive(Default)]
+struct FieldConstraintsData {
+    required: bool,
+    min: Option<syn::Expr>,
+    max: Option<syn::Expr>,
+    min_length: Option<syn::Expr>,
+    max_length: Option<syn::Expr>,
+    pattern: Option<String>,
+    email: bool,
+    url: bool,
 }
 
-impl FieldType {
-    fn from_syn_type(ty: &syn::Type) -> Result<Self, CompileError> {
-        let field_type_ident = match &ty {
-            syn::Type::Path(syn::TypePath {
-                path: syn::Path { segments, .. },
-                ..
-            }) => Ok(segments[0].ident.clone()),
-            _ => Err(CompileError::new("Field type not supported")),
-        }?;
+impl FieldConstraintsData {
+    fn to_tokens(&self) -> proc_macro2::TokenStream {
+        let required = self.required;
+        let email = self.email;
+        let url = self.url;
+        let min = option_tokens(self.min.as_ref().map(|min| quote! { (#min) as f64 }));
+        let max = option_tokens(self.max.as_ref().map(|max| quote! { (#max) as f64 }));
+        let min_length = option_tokens(self.min_length.as_ref().map(|min| quote! { #min }));
+        let max_length = option_tokens(self.max_length.as_ref().map(|max| quote! { #max }));
+        // The pattern was checked in `parse_pattern`, so it can't fail to compile here
+        let pattern = option_tokens(self.pattern.as_ref().map(|pattern| {
+            quote! { razer_core::Pattern::new(#pattern).expect("invalid pattern") }
+        }));
 
-        match field_type_ident.to_string().as_str() {
-            "String" => Ok(FieldType::String),
-            "u32" | "i32" => Ok(FieldType::Number),
-            "bool" => Ok(FieldType::Boolean),
-            // TODO Better error handling
-            _ => Err(CompileError::new("Field type not supported")),
+        quote! {
+            razer_core::FieldConstraints {
+                required: #required,
+                min: #min,
+                max: #max,
+                min_length: #min_length,
+                max_length: #max_length,
+                pattern: #pattern,
+                email: #email,
+                url: #url,
+            }
         }
     }
 }
 
-fn derive_admin_resource_impl(input: DeriveInput) -> Result<TokenStream, CompileError> {
-    let struct_fields = match &input.data {
-        syn::Data::Struct(syn::DataStruct {
-            fields: syn::Fields::Named(FieldsNamed { named: fields, .. }),
+fn option_tokens(value: Option<proc_macro2::TokenStream>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+/// Parses the `min = 1, max = 64` inside `length(...)`
+fn parse_length_args(
+    list: &syn::MetaList,
+    constraints: &mut FieldConstraintsData,
+) -> syn::Result<()> {
+    let args = list.parse_args_with(<Punctuated<Meta, Token![,]>>::parse_terminated)?;
+
+    for arg in args {
+        match arg {
+            Meta::NameValue(name_value) if name_value.path.is_ident("min") => {
+                constraints.min_length = Some(name_value.value);
+            }
+            Meta::NameValue(name_value) if name_value.path.is_ident("max") => {
+                constraints.max_length = Some(name_value.value);
+            }
+            arg => {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "expected `min = ..` or `max = ..` in `length(..)`",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_pattern(value: &syn::Expr) -> syn::Result<String> {
+    let pattern = match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(pattern),
             ..
-        }) => Ok(fields.iter().map(|field| {
-            let admin_attr = field
-                .attrs
-                .iter()
-                .find(|it| it.path().get_ident().is_some_and(|it| it == "admin"));
+        }) => pattern,
+        value => {
+            return Err(syn::Error::new_spanned(
+                value,
+                "expected `regex` to be a string literal",
+            ));
+        }
+    };
 
-            let args = admin_attr.map(|it| {
-                // TODO Handle error
-                it.parse_args_with(<Punctuated<Meta, Token![,]>>::parse_terminated)
-                    .unwrap()
-            });
+    // Catch invalid patterns at compile time rather than when the form is first validated
+    if let Err(e) = regex::Regex::new(&pattern.value()) {
+        return Err(syn::Error::new_spanned(
+            pattern,
+            format!("invalid regex: {}", e),
+        ));
+    }
 
-            let mut is_readonly = false;
+    Ok(pattern.value())
+}
 
-            if let Some(args) = args {
-                args.iter().for_each(|attr| {
-                    dbg!(attr);
-                });
+/// Reads `widget = "..."`, mapping the widget's name to its marker type in `razer_core::widget`
+fn parse_widget(name_value: &syn::MetaNameValue) -> syn::Result<syn::Ident> {
+    let widget = parse_string_literal(name_value)?;
+    let marker = match widget.value().as_str() {
+        "checkbox" => "Checkbox",
+        "toggle" => "Toggle",
+        "select" => "Select",
+        "radio" => "Radio",
+        _ => {
+            return Err(syn::Error::new_spanned(
+                widget,
+                "unknown widget, expected one of `checkbox`, `toggle`, `select` or `radio`",
+            ));
+        }
+    };
 
-                args.iter().for_each(|arg| {
-                    match arg {
-                        Meta::Path(path) if path.get_ident().is_some_and(|it| it == "readonly") => {
-                            is_readonly = true;
-                        },
-                        _ => todo!()
-                    }
-                });
-            }
+    Ok(syn::Ident::new(marker, widget.span()))
+}
 
-            AdminFieldData {
-                name: field.ident.clone().expect("Named fields must have idents"),
-                ty: field.ty.clone(),
-                is_readonly,
+/// A field's `#[serde(...)]` attributes without any `rename` or `alias`. Submitted forms are keyed
+/// by the field's name, so the create input has to be read by it too.
+fn serde_attrs_without_renames(attrs: &[syn::Attribute]) -> syn::Result<Vec<syn::Attribute>> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .map(|attr| {
+            let args: Vec<Meta> = attr
+                .parse_args_with(<Punctuated<Meta, Token![,]>>::parse_terminated)?
+                .into_iter()
+                .filter(|arg| !arg.path().is_ident("rename") && !arg.path().is_ident("alias"))
+                .collect();
+
+            Ok((!args.is_empty()).then(|| syn::parse_quote! { #[serde(#(#args),*)] }))
+        })
+        .filter_map(syn::Result::transpose)
+        .collect()
+}
+
+/// Reads a field along with its `#[admin(...)]` arguments
+fn parse_field(field: &syn::Field) -> syn::Result<AdminFieldData> {
+    let mut is_readonly = false;
+    let mut is_skip_create = false;
+    let mut is_sortable = false;
+    let mut is_searchable = false;
+    let mut display_format = None;
+    let mut widget = None;
+    let mut relation = None;
+    let mut constraints = FieldConstraintsData::default();
+
+    if let Some(admin_attr) = find_admin_attr(&field.attrs) {
+        for arg in parse_admin_args(admin_attr)? {
+            match arg {
+                Meta::Path(path) if path.is_ident("readonly") => {
+                    is_readonly = true;
+                }
+                Meta::Path(path) if path.is_ident("sortable") => {
+                    is_sortable = true;
+                }
+                Meta::Path(path) if path.is_ident("searchable") => {
+                    is_searchable = true;
+                }
+                Meta::Path(path) if path.is_ident("skip_create") => {
+                    is_skip_create = true;
+                }
+                Meta::Path(path) if path.is_ident("required") => {
+                    constraints.required = true;
+                }
+                Meta::Path(path) if path.is_ident("email") => {
+                    constraints.email = true;
+                }
+                Meta::Path(path) if path.is_ident("url") => {
+                    constraints.url = true;
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("min") => {
+                    constraints.min = Some(name_value.value);
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("max") => {
+                    constraints.max = Some(name_value.value);
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("regex") => {
+                    constraints.pattern = Some(parse_pattern(&name_value.value)?);
+                }
+                Meta::List(list) if list.path.is_ident("length") => {
+                    parse_length_args(&list, &mut constraints)?;
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("format") => {
+                    display_format = Some(parse_string_literal(&name_value)?);
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("widget") => {
+                    widget = Some(parse_widget(&name_value)?);
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("relation") => {
+                    relation = Some(parse_string_literal(&name_value)?);
+                }
+                arg => {
+                    return Err(syn::Error::new_spanned(
+                        arg,
+                        "unknown `admin` field argument, expected one of `readonly`, `sortable`, \
+                         `searchable`, `skip_create`, `required`, `email`, `url`, `min`, `max`, \
+                         `regex`, `length`, `format`, `widget` or `relation`",
+                    ));
+                }
             }
-        })),
-        _ => Err(CompileError::new("Aa")),
-    }?;
+        }
+    }
 
-    let struct_ident = input.ident;
+    Ok(AdminFieldData {
+        name: field
+            .ident
+            .clone()
+            .ok_or_else(|| syn::Error::new_spanned(field, "expected a named field"))?,
+        ty: field.ty.clone(),
+        vis: field.vis.clone(),
+        serde_attrs: serde_attrs_without_renames(&field.attrs)?,
+        is_readonly,
+        is_skip_create,
+        is_sortable,
+        is_searchable,
+        display_format,
+        widget,
+        relation,
+        constraints,
+    })
+}
 
-    let field_config_defs = struct_fields.clone().map(|field| {
-        let field_id = field.name.to_string();
-        let field_name = field_id_to_display_name(&field_id.to_string());
-        let is_readonly = field.is_readonly;
+/// Reads the `#[admin(...)]` arguments on the struct itself
+fn parse_resource_args(input: &DeriveInput) -> syn::Result<AdminResourceDeriveArgs> {
+    let admin_attr = find_admin_attr(&input.attrs).ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "missing `#[admin(...)]` attribute, e.g. `#[admin(name = \"Customers\")]`",
+        )
+    })?;
+
+    let mut resource_args = AdminResourceDeriveArgs::default();
+    for arg in parse_admin_args(admin_attr)? {
+        match arg {
+            Meta::NameValue(name_value) if name_value.path.is_ident("name") => {
+                resource_args.name = Some(parse_string_literal(&name_value)?.value());
+            }
+            Meta::NameValue(name_value) if name_value.path.is_ident("path") => {
+                resource_args.path = Some(parse_string_literal(&name_value)?.value());
+            }
+            Meta::NameValue(name_value) if name_value.path.is_ident("id") => {
+                resource_args.id = Some(parse_string_literal(&name_value)?);
+            }
+            Meta::NameValue(name_value) if name_value.path.is_ident("label") => {
+                resource_args.label = Some(parse_string_literal(&name_value)?);
+            }
+            Meta::NameValue(name_value) if name_value.path.is_ident("description") => {
+                resource_args.description = Some(parse_string_literal(&name_value)?.value());
+            }
+            Meta::NameValue(name_value) if name_value.path.is_ident("create_input") => {
+                let create_input = parse_string_literal(&name_value)?;
+                resource_args.create_input = Some(create_input.parse()?);
+            }
+            arg => {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "unknown `admin` argument, expected one of `name`, `path`, `id`, `label`, \
+                     `description` or `create_input`",
+                ));
+            }
+        }
+    }
 
-        // TODO Don't unwrap
-        let function_ident = match FieldType::from_syn_type(&field.ty).unwrap() {
-            FieldType::String => quote! { create_text_config },
-            FieldType::Number => quote! { create_number_config },
-            FieldType::Boolean => quote! { create_boolean_config },
-        };
+    Ok(resource_args)
+}
 
+/// Generates the `create_input` struct, holding every field which is set when an item is created,
+/// along with conversions between it and the model
+fn create_input_defs(
+    vis: &syn::Visibility,
+    struct_ident: &Ident,
+    create_input_ident: &Ident,
+    fields: &[AdminFieldData],
+) -> proc_macro2::TokenStream {
+    let (input_fields, other_fields): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .partition(|field| !field.is_readonly && !field.is_skip_create);
+
+    let input_field_defs = input_fields.iter().map(|field| {
+        let AdminFieldData {
+            name,
+            ty,
+            vis,
+            serde_attrs,
+            ..
+        } = field;
         quote! {
-            razer_core::FieldConfig::#function_ident(#field_id.to_string(), #field_name.to_string(), #is_readonly)
+            #(#serde_attrs)*
+            #vis #name: #ty
         }
     });
+    let input_field_names: Vec<_> = input_fields.iter().map(|field| &field.name).collect();
+    let other_field_params = other_fields.iter().map(|field| {
+        let AdminFieldData { name, ty, .. } = field;
+        quote! { #name: #ty }
+    });
+    let other_field_names: Vec<_> = other_fields.iter().map(|field| &field.name).collect();
+
+    quote! {
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #vis struct #create_input_ident {
+            #(#input_field_defs),*
+        }
+
+        impl #create_input_ident {
+            /// Builds the item from the input, given the fields which aren't part of it (read only
+            /// and `skip_create` fields)
+            #[allow(clippy::too_many_arguments, dead_code)]
+            #vis fn into_item(self, #(#other_field_params),*) -> #struct_ident {
+                #struct_ident {
+                    #(#input_field_names: self.#input_field_names,)*
+                    #(#other_field_names,)*
+                }
+            }
+
+            /// Overwrites the item's fields with the input, e.g. when updating it
+            #[allow(dead_code)]
+            #vis fn apply_to(self, item: &mut #struct_ident) {
+                #(item.#input_field_names = self.#input_field_names;)*
+            }
+        }
+
+        impl From<#struct_ident> for #create_input_ident {
+            fn from(item: #struct_ident) -> Self {
+                Self {
+                    #(#input_field_names: item.#input_field_names),*
+                }
+            }
+        }
+    }
+}
+
+fn derive_admin_resource_impl(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    const EXPECTED_NAMED_STRUCT: &str =
+        "`AdminResource` can only be derived for structs with named fields";
+
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(FieldsNamed { named: fields, .. }),
+            ..
+        }) => fields,
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Unnamed(fields),
+            ..
+        }) => return Err(syn::Error::new_spanned(fields, EXPECTED_NAMED_STRUCT)),
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Unit,
+            ..
+        }) => return Err(syn::Error::new_spanned(&input.ident, EXPECTED_NAMED_STRUCT)),
+        syn::Data::Enum(syn::DataEnum { enum_token, .. }) => {
+            return Err(syn::Error::new_spanned(enum_token, EXPECTED_NAMED_STRUCT));
+        }
+        syn::Data::Union(syn::DataUnion { union_token, .. }) => {
+            return Err(syn::Error::new_spanned(union_token, EXPECTED_NAMED_STRUCT));
+        }
+    };
 
-    let admin_attr = input
-        .attrs
+    let resource_args = parse_resource_args(&input)?;
+    let struct_fields = fields
         .iter()
-        .find(|it| it.path().get_ident().is_some_and(|it| it == "admin"));
-
-    match admin_attr {
-        Some(admin_attr) => {
-            let args = admin_attr
-                .parse_args_with(<Punctuated<Meta, Token![,]>>::parse_terminated)
-                .map_err(|e| {
-                    todo!()
-                    // CompileError::no_file_info(
-                    //     format_args!("unable to parse template arguments: {e}"),
-                    //     Some(attr.path().span()),
-                    // )
-                });
-        }
-        None => todo!(),
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let struct_ident = input.ident;
+
+    let field_config_defs = struct_fields
+        .iter()
+        .map(|field| {
+            let field_id = field.name.to_string();
+            let field_name = field_id_to_display_name(&field_id);
+            let is_readonly = field.is_readonly;
+            let is_sortable = field.is_sortable;
+            let is_searchable = field.is_searchable;
+            let is_skip_create = field.is_skip_create;
+            let constraints = field.constraints.to_tokens();
+
+            // Spanned to the field's type so a missing `AdminField` impl, or one for the picked
+            // widget, is reported against it
+            let ty = &field.ty;
+            let default_config = match &field.widget {
+                Some(widget) => {
+                    let widget = quote! { razer_core::widget::#widget };
+                    quote_spanned! {ty.span()=>
+                        <#ty as razer_core::AdminWidgetField<#widget>>::widget_field_config(
+                            #field_id.to_string(),
+                            #field_name.to_string(),
+                            #is_readonly,
+                        )
+                    }
+                }
+                None => quote_spanned! {ty.span()=>
+                    <#ty as razer_core::AdminField>::field_config(
+                        #field_id.to_string(),
+                        #field_name.to_string(),
+                        #is_readonly,
+                    )
+                },
+            };
+            // Likewise a type without display formats is reported against the field's type
+            let display_format = field.display_format.as_ref().map(|format| {
+                quote_spanned! {ty.span()=> .with_display_format::<#ty>(#format) }
+            });
+            let relation = field
+                .relation
+                .as_ref()
+                .map(|relation| quote! { .with_relation(#relation) });
+
+            quote! {
+                razer_core::FieldConfig {
+                    sortable: #is_sortable,
+                    searchable: #is_searchable,
+                    skip_create: #is_skip_create,
+                    ..#default_config
+                }
+                .with_constraints(#constraints)
+                #display_format
+                #relation
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Anything not given is worked out from the struct, e.g. `MyModel` is served at `my_model`
+    // with the name "My model"
+    let struct_snake_case = ident_to_snake_case(&struct_ident.to_string());
+    let resource_name = resource_args
+        .name
+        .unwrap_or_else(|| field_id_to_display_name(&struct_snake_case));
+    let resource_path = resource_args.path.unwrap_or(struct_snake_case);
+    let id_field_id = match &resource_args.id {
+        Some(id) => id.value(),
+        None => "id".to_string(),
+    };
+    let resource_description = match resource_args.description {
+        Some(description) => quote! { Some(#description.to_string()) },
+        None => quote! { None },
+    };
+
+    if !struct_fields.iter().any(|field| field.name == id_field_id) {
+        let message = format!("no field `{}` to use as the id", id_field_id);
+        return Err(match &resource_args.id {
+            Some(id) => syn::Error::new_spanned(id, message),
+            None => syn::Error::new_spanned(
+                &struct_ident,
+                format!(
+                    "{}, set the id field with `#[admin(id = \"...\")]`",
+                    message
+                ),
+            ),
+        });
     }
 
-    let field_configs_struct_ident: Ident = Ident::new(&format!("{}FieldConfigs", struct_ident), proc_macro2::Span::call_site());
-    let field_configs_struct_fields = struct_fields.clone().map(|field| {
-        let field_ident = field.name;
-        quote! {
-            #field_ident: razer_core::FieldConfig
+    // Items are labelled by their id unless another field is given
+    let label_field_id = match &resource_args.label {
+        Some(label)
+            if !struct_fields
+                .iter()
+                .any(|field| field.name == label.value()) =>
+        {
+            return Err(syn::Error::new_spanned(
+                label,
+                format!("no field `{}` to use as the label", label.value()),
+            ));
         }
-    });
+        Some(label) => label.value(),
+        None => id_field_id.clone(),
+    };
 
-    let field_configs_struct_assignments = struct_fields.clone().zip(field_config_defs.clone()).map(|(field, def)| {
-        let field_ident = field.name;
+    let create_input = resource_args
+        .create_input
+        .map(|create_input_ident| {
+            create_input_defs(
+                &input.vis,
+                &struct_ident,
+                &create_input_ident,
+                &struct_fields,
+            )
+        })
+        .unwrap_or_default();
+
+    let field_configs_struct_ident: Ident = Ident::new(
+        &format!("{}FieldConfigs", struct_ident),
+        proc_macro2::Span::call_site(),
+    );
+    let field_configs_struct_fields = struct_fields.iter().map(|field| {
+        let field_ident = &field.name;
+        let vis = &field.vis;
         quote! {
-            #field_ident: #def
+            #vis #field_ident: razer_core::FieldConfig
         }
     });
 
+    let field_configs_struct_assignments =
+        struct_fields
+            .iter()
+            .zip(&field_config_defs)
+            .map(|(field, def)| {
+                let field_ident = &field.name;
+                quote! {
+                    #field_ident: #def
+                }
+            });
+
     let field_configs_struct_into = {
-        let assignments = struct_fields.map(|field| {
-            let field_ident = field.name;
+        let assignments = struct_fields.iter().map(|field| {
+            let field_ident = &field.name;
             quote! {
                 self.#field_ident
             }
@@ -208,19 +630,23 @@ fn derive_admin_resource_impl(input: DeriveInput) -> Result<TokenStream, Compile
         }
     };
 
+    // Everything generated is as visible as the model, so it can be registered from any module
+    // that can see it
+    let vis = &input.vis;
+
     Ok(quote! {
-        struct #field_configs_struct_ident {
+        #vis struct #field_configs_struct_ident {
             #(#field_configs_struct_fields),*
         }
 
         impl #struct_ident {
-            fn default_field_configs() -> Vec<razer_core::FieldConfig> {
+            #vis fn default_field_configs() -> Vec<razer_core::FieldConfig> {
                 vec![
                     #(#field_config_defs),*
                 ]
             }
 
-            fn field_configs() -> #field_configs_struct_ident  {
+            #vis fn field_configs() -> #field_configs_struct_ident  {
                 #field_configs_struct_ident {
                     #(#field_configs_struct_assignments),*
                 }
@@ -228,6 +654,189 @@ fn derive_admin_resource_impl(input: DeriveInput) -> Result<TokenStream, Compile
         }
 
         #field_configs_struct_into
+
+        #create_input
+
+        impl razer_core::AdminModel for #struct_ident {
+            fn admin_name() -> String {
+                #resource_name.to_string()
+            }
+
+            fn admin_description() -> Option<String> {
+                #resource_description
+            }
+
+            fn admin_path() -> String {
+                #resource_path.to_string()
+            }
+
+            fn admin_id_field_id() -> String {
+                #id_field_id.to_string()
+            }
+
+            fn admin_label_field_id() -> String {
+                #label_field_id.to_string()
+            }
+
+            fn admin_field_configs() -> Vec<razer_core::FieldConfig> {
+                #struct_ident::default_field_configs()
+            }
+        }
+
+        impl #struct_ident {
+            /// Serves the model from a data source, as described by its `#[admin(...)]` attributes
+            #vis fn admin_resource<TDataSource>(
+                data_source: TDataSource,
+            ) -> razer_core::ModelResource<Self, TDataSource> {
+                razer_core::ModelResource::new(data_source)
+            }
+        }
+    })
+}
+
+fn derive_admin_select_impl(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let variants = match &input.data {
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => variants,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`AdminSelect` can only be derived for enums with unit variants",
+            ));
+        }
+    };
+
+    let enum_ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let variant_labels = variants
+        .iter()
+        .map(|variant| {
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    &variant.fields,
+                    "`AdminSelect` variants can't have fields",
+                ));
+            }
+
+            let mut label = None;
+            if let Some(admin_attr) = find_admin_attr(&variant.attrs) {
+                for arg in parse_admin_args(admin_attr)? {
+                    match arg {
+                        Meta::NameValue(name_value) if name_value.path.is_ident("label") => {
+                            label = Some(parse_string_literal(&name_value)?.value());
+                        }
+                        arg => {
+                            return Err(syn::Error::new_spanned(
+                                arg,
+                                "unknown `admin` variant argument, expected `label`",
+                            ));
+                        }
+                    }
+                }
+            }
+
+            let label = label.unwrap_or_else(|| {
+                field_id_to_display_name(&ident_to_snake_case(&variant.ident.to_string()))
+            });
+            Ok((&variant.ident, label))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let variant_idents = variant_labels.iter().map(|(ident, _)| ident);
+    let label_arms = variant_labels.iter().map(|(ident, label)| {
+        quote! { #enum_ident::#ident => #label.to_string() }
+    });
+
+    Ok(quote! {
+        impl #impl_generics razer_core::AdminSelect for #enum_ident #ty_generics #where_clause {
+            fn variants() -> Vec<Self> {
+                vec![#(#enum_ident::#variant_idents),*]
+            }
+
+            fn label(&self) -> String {
+                match self {
+                    #(#label_arms),*
+                }
+            }
+        }
+
+        impl #impl_generics razer_core::AdminField for #enum_ident #ty_generics #where_clause {
+            fn field_config(
+                field_id: String,
+                display_name: String,
+                read_only: bool,
+            ) -> razer_core::FieldConfig {
+                razer_core::FieldConfig::create_select_config(
+                    field_id,
+                    display_name,
+                    read_only,
+                    razer_core::select_options::<Self>(),
+                )
+            }
+        }
+
+        impl #impl_generics razer_core::AdminWidgetField<razer_core::widget::Select>
+            for #enum_ident #ty_generics #where_clause
+        {
+            fn widget_field_config(
+                field_id: String,
+                display_name: String,
+                read_only: bool,
+            ) -> razer_core::FieldConfig {
+                <Self as razer_core::AdminField>::field_config(field_id, display_name, read_only)
+            }
+        }
+
+        impl #impl_generics razer_core::AdminWidgetField<razer_core::widget::Radio>
+            for #enum_ident #ty_generics #where_clause
+        {
+            fn widget_field_config(
+                field_id: String,
+                display_name: String,
+                read_only: bool,
+            ) -> razer_core::FieldConfig {
+                razer_core::FieldConfig::create_radio_config(
+                    field_id,
+                    display_name,
+                    read_only,
+                    razer_core::select_options::<Self>(),
+                )
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_id_to_display_name_works() {
+        assert_eq!(field_id_to_display_name("attr_name"), "Attr name");
+        assert_eq!(field_id_to_display_name("hello"), "Hello");
+        assert_eq!(field_id_to_display_name("abc_def_hi"), "Abc def hi");
+    }
+
+    #[test]
+    fn serde_attrs_without_renames_works() {
+        let field: syn::Field = syn::parse_quote! {
+            #[serde(rename = "fullName", default)]
+            #[serde(alias = "name")]
+            #[admin(required)]
+            full_name: String
+        };
+        let attrs = serde_attrs_without_renames(&field.attrs).unwrap();
+
+        assert_eq!(attrs.len(), 1);
+        assert_eq!(
+            attrs[0].to_token_stream().to_string(),
+            quote! { #[serde(default)] }.to_string()
+        );
+    }
+
+    #[test]
+    fn ident_to_snake_case_works() {
+        assert_eq!(ident_to_snake_case("MyModel"), "my_model");
+        assert_eq!(ident_to_snake_case("Customer"), "customer");
     }
-    .into())
 }
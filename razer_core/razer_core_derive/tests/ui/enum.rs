@@ -0,0 +1,10 @@
+use razer_core_derive::AdminResource;
+
+#[derive(AdminResource)]
+#[admin(name = "Statuses")]
+enum Status {
+    Active,
+    Archived,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use razer_core_derive::AdminResource;
+
+#[derive(AdminResource)]
+#[admin(name = "Customers")]
+struct Customer {
+    id: i32,
+    #[admin(regex = "[a-z")]
+    name: String,
+}
+
+fn main() {}
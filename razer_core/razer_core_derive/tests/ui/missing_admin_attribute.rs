@@ -0,0 +1,9 @@
+use razer_core_derive::AdminResource;
+
+#[derive(AdminResource)]
+struct Customer {
+    id: i32,
+    name: String,
+}
+
+fn main() {}
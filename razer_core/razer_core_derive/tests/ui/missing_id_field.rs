@@ -0,0 +1,10 @@
+use razer_core_derive::AdminResource;
+
+#[derive(AdminResource)]
+#[admin(name = "Customers", id = "customer_id")]
+struct Customer {
+    id: i32,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use razer_core_derive::AdminSelect;
+
+#[derive(AdminSelect)]
+enum Status {
+    Draft,
+    Published { at: String },
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use razer_core_derive::AdminResource;
+
+#[derive(AdminResource)]
+#[admin(name = "Customers")]
+struct Customer(i32, String);
+
+fn main() {}
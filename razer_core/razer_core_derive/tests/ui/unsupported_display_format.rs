@@ -0,0 +1,11 @@
+use razer_core_derive::AdminResource;
+
+#[derive(AdminResource)]
+#[admin(name = "Customers")]
+struct Customer {
+    id: i32,
+    #[admin(format = "%d %B %Y")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use razer_core_derive::AdminResource;
+
+struct Tags(Vec<String>);
+
+#[derive(AdminResource)]
+#[admin(name = "Customers")]
+struct Customer {
+    id: i32,
+    tags: Tags,
+}
+
+fn main() {}
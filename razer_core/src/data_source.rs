@@ -0,0 +1,560 @@
+use std::{future::Future, marker::PhantomData, str::FromStr};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    AdminError, BulkDeleteResult, FieldConfig, ListPage, ListQuery, RelatedList, Resource,
+    ValidationErrors,
+};
+
+/// Everything the admin needs to show and edit one kind of item: how the resource is presented
+/// and the operations to read and write its items.
+///
+/// Implement this for whatever stores the items, e.g. a database table. The closure based
+/// [`Resource`] is one implementation.
+pub trait AdminDataSource<TContext: Clone + Send + 'static>: Send + Sync + 'static {
+    type Id: ToString + FromStr + Clone + Send + Sync + 'static;
+    type Item: Serialize + Send + 'static;
+    type CreateInput: DeserializeOwned + Send + Sync + 'static;
+    type UpdateInput: DeserializeOwned + Send + Sync + 'static;
+
+    // How the resource is presented. Data sources for a model deriving `AdminResource` can leave
+    // these as the defaults and be wrapped in a `ModelResource`, which takes them from the model
+
+    /// Defaults to the name of the item type
+    fn name(&self) -> String {
+        let type_name = std::any::type_name::<Self::Item>();
+        // Drop the module path and any generics
+        let type_name = type_name.split('<').next().unwrap_or(type_name);
+        type_name
+            .rsplit("::")
+            .next()
+            .unwrap_or(type_name)
+            .to_string()
+    }
+
+    fn description(&self) -> Option<String> {
+        None
+    }
+
+    /// The url path segment the resource is served under. Defaults to the lowercased name.
+    fn path(&self) -> String {
+        self.name().to_lowercase().replace(' ', "_")
+    }
+
+    /// The field of an item holding its id
+    fn id_field_id(&self) -> String {
+        "id".to_string()
+    }
+
+    /// The field items are labelled by where they're referred to, e.g. in the fields of other
+    /// resources which relate to them. Defaults to the id field.
+    fn label_field_id(&self) -> String {
+        self.id_field_id()
+    }
+
+    fn field_configs(&self) -> Vec<FieldConfig> {
+        vec![]
+    }
+
+    /// The lists of related items shown on an item's detail page
+    fn related_lists(&self) -> Vec<RelatedList> {
+        vec![]
+    }
+
+    fn list(
+        &self,
+        context: TContext,
+        query: ListQuery,
+    ) -> impl Future<Output = Result<ListPage<Self::Item>, AdminError>> + Send;
+
+    /// The number of items, shown on the admin's home page. By default the items aren't counted,
+    /// override this if the data source can count them cheaply.
+    fn count(
+        &self,
+        _context: TContext,
+    ) -> impl Future<Output = Result<Option<u64>, AdminError>> + Send {
+        async { Ok(None) }
+    }
+
+    fn get(
+        &self,
+        context: TContext,
+        id: Self::Id,
+    ) -> impl Future<Output = Result<Self::Item, AdminError>> + Send;
+
+    fn create(
+        &self,
+        context: TContext,
+        input: Self::CreateInput,
+    ) -> impl Future<Output = Result<Self::Item, AdminError>> + Send;
+
+    fn update(
+        &self,
+        context: TContext,
+        id: Self::Id,
+        input: Self::UpdateInput,
+    ) -> impl Future<Output = Result<Self::Item, AdminError>> + Send;
+
+    fn delete(
+        &self,
+        context: TContext,
+        id: Self::Id,
+    ) -> impl Future<Output = Result<(), AdminError>> + Send;
+
+    /// Deletes several items at once. By default they are deleted one at a time, override this if
+    /// the data source can do it in one go.
+    fn delete_many(
+        &self,
+        context: TContext,
+        ids: Vec<Self::Id>,
+    ) -> impl Future<Output = BulkDeleteResult<Self::Id>> + Send {
+        delete_each(context, ids, |context, id| self.delete(context, id))
+    }
+
+    /// Checks the whole create input, once every field has passed its own validation. Use this
+    /// for rules involving several fields or which need the data source, e.g. checking an email
+    /// is unique.
+    fn validate_create(
+        &self,
+        _context: TContext,
+        _input: &Self::CreateInput,
+    ) -> impl Future<Output = Result<(), ValidationErrors>> + Send {
+        async { Ok(()) }
+    }
+
+    /// As with `validate_create`. The id is given so an item can be excluded from checks against
+    /// itself.
+    fn validate_update(
+        &self,
+        _context: TContext,
+        _id: &Self::Id,
+        _input: &Self::UpdateInput,
+    ) -> impl Future<Output = Result<(), ValidationErrors>> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// How a model is presented in the admin, implemented by `#[derive(AdminResource)]` from the
+/// `#[admin(...)]` attributes on the struct and its fields
+pub trait AdminModel {
+    fn admin_name() -> String;
+    fn admin_description() -> Option<String>;
+    fn admin_path() -> String;
+    fn admin_id_field_id() -> String;
+    fn admin_label_field_id() -> String {
+        Self::admin_id_field_id()
+    }
+    fn admin_field_configs() -> Vec<FieldConfig>;
+}
+
+/// Deletes the items one at a time, recording which of them failed. This is how data sources which
+/// can't delete several items at once implement `delete_many`.
+async fn delete_each<
+    TContext: Clone,
+    TId: Clone,
+    TFuture: Future<Output = Result<(), AdminError>>,
+>(
+    context: TContext,
+    ids: Vec<TId>,
+    delete: impl Fn(TContext, TId) -> TFuture,
+) -> BulkDeleteResult<TId> {
+    let mut result = BulkDeleteResult::default();
+
+    for id in ids {
+        match delete(context.clone(), id.clone()).await {
+            Ok(()) => result.deleted.push(id),
+            Err(_) => result.failed.push(id),
+        }
+    }
+
+    result
+}
+
+/// A data source for a model which implements [`AdminModel`], presented as the model describes.
+/// The field configs can be replaced to customise individual fields.
+pub struct ModelResource<TModel, TDataSource> {
+    data_source: TDataSource,
+    field_configs: Vec<FieldConfig>,
+    related_lists: Vec<RelatedList>,
+    model: PhantomData<fn() -> TModel>,
+}
+
+impl<TModel: AdminModel, TDataSource> ModelResource<TModel, TDataSource> {
+    pub fn new(data_source: TDataSource) -> Self {
+        Self {
+            data_source,
+            field_configs: TModel::admin_field_configs(),
+            related_lists: vec![],
+            model: PhantomData,
+        }
+    }
+
+    pub fn with_field_configs(self, field_configs: Vec<FieldConfig>) -> Self {
+        Self {
+            field_configs,
+            ..self
+        }
+    }
+
+    /// Shows a list of related items on the detail page, see [`RelatedList`]
+    pub fn with_related_list(mut self, related_list: RelatedList) -> Self {
+        self.related_lists.push(related_list);
+        self
+    }
+}
+
+impl<
+        TContext: Clone + Send + 'static,
+        TModel: AdminModel + Serialize + Send + 'static,
+        TDataSource: AdminDataSource<TContext, Item = TModel>,
+    > AdminDataSource<TContext> for ModelResource<TModel, TDataSource>
+{
+    type Id = TDataSource::Id;
+    type Item = TModel;
+    type CreateInput = TDataSource::CreateInput;
+    type UpdateInput = TDataSource::UpdateInput;
+
+    fn name(&self) -> String {
+        TModel::admin_name()
+    }
+
+    fn description(&self) -> Option<String> {
+        TModel::admin_description()
+    }
+
+    fn path(&self) -> String {
+        TModel::admin_path()
+    }
+
+    fn id_field_id(&self) -> String {
+        TModel::admin_id_field_id()
+    }
+
+    fn label_field_id(&self) -> String {
+        TModel::admin_label_field_id()
+    }
+
+    fn field_configs(&self) -> Vec<FieldConfig> {
+        self.field_configs.clone()
+    }
+
+    fn related_lists(&self) -> Vec<RelatedList> {
+        self.related_lists.clone()
+    }
+
+    fn list(
+        &self,
+        context: TContext,
+        query: ListQuery,
+    ) -> impl Future<Output = Result<ListPage<TModel>, AdminError>> + Send {
+        self.data_source.list(context, query)
+    }
+
+    fn count(
+        &self,
+        context: TContext,
+    ) -> impl Future<Output = Result<Option<u64>, AdminError>> + Send {
+        self.data_source.count(context)
+    }
+
+    fn get(
+        &self,
+        context: TContext,
+        id: Self::Id,
+    ) -> impl Future<Output = Result<TModel, AdminError>> + Send {
+        self.data_source.get(context, id)
+    }
+
+    fn create(
+        &self,
+        context: TContext,
+        input: Self::CreateInput,
+    ) -> impl Future<Output = Result<TModel, AdminError>> + Send {
+        self.data_source.create(context, input)
+    }
+
+    fn update(
+        &self,
+        context: TContext,
+        id: Self::Id,
+        input: Self::UpdateInput,
+    ) -> impl Future<Output = Result<TModel, AdminError>> + Send {
+        self.data_source.update(context, id, input)
+    }
+
+    fn delete(
+        &self,
+        context: TContext,
+        id: Self::Id,
+    ) -> impl Future<Output = Result<(), AdminError>> + Send {
+        self.data_source.delete(context, id)
+    }
+
+    fn delete_many(
+        &self,
+        context: TContext,
+        ids: Vec<Self::Id>,
+    ) -> impl Future<Output = BulkDeleteResult<Self::Id>> + Send {
+        self.data_source.delete_many(context, ids)
+    }
+
+    fn validate_create(
+        &self,
+        context: TContext,
+        input: &Self::CreateInput,
+    ) -> impl Future<Output = Result<(), ValidationErrors>> + Send {
+        self.data_source.validate_create(context, input)
+    }
+
+    fn validate_update(
+        &self,
+        context: TContext,
+        id: &Self::Id,
+        input: &Self::UpdateInput,
+    ) -> impl Future<Output = Result<(), ValidationErrors>> + Send {
+        self.data_source.validate_update(context, id, input)
+    }
+}
+
+impl<
+        TContext: Clone + Send + 'static,
+        TId: ToString + FromStr + Clone + Send + Sync + 'static,
+        TItem: Serialize + Send + 'static,
+        TCreateInput: DeserializeOwned + Send + Sync + 'static,
+        TUpdateInput: DeserializeOwned + Send + Sync + 'static,
+    > AdminDataSource<TContext> for Resource<TContext, TId, TItem, TCreateInput, TUpdateInput>
+{
+    type Id = TId;
+    type Item = TItem;
+    type CreateInput = TCreateInput;
+    type UpdateInput = TUpdateInput;
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn id_field_id(&self) -> String {
+        self.id_field_id.clone()
+    }
+
+    fn field_configs(&self) -> Vec<FieldConfig> {
+        self.field_configs.clone()
+    }
+
+    fn related_lists(&self) -> Vec<RelatedList> {
+        self.related_lists.clone()
+    }
+
+    fn list(
+        &self,
+        context: TContext,
+        query: ListQuery,
+    ) -> impl Future<Output = Result<ListPage<TItem>, AdminError>> + Send {
+        (self.list_items)(context, query)
+    }
+
+    fn count(
+        &self,
+        context: TContext,
+    ) -> impl Future<Output = Result<Option<u64>, AdminError>> + Send {
+        let count = self
+            .count_items
+            .as_ref()
+            .map(|count_items| (count_items)(context));
+
+        async move {
+            match count {
+                Some(count) => count.await.map(Some),
+                None => Ok(None),
+            }
+        }
+    }
+
+    fn get(
+        &self,
+        context: TContext,
+        id: TId,
+    ) -> impl Future<Output = Result<TItem, AdminError>> + Send {
+        (self.get_item)(context, id)
+    }
+
+    fn create(
+        &self,
+        context: TContext,
+        input: TCreateInput,
+    ) -> impl Future<Output = Result<TItem, AdminError>> + Send {
+        (self.create_item)(context, input)
+    }
+
+    fn update(
+        &self,
+        context: TContext,
+        id: TId,
+        input: TUpdateInput,
+    ) -> impl Future<Output = Result<TItem, AdminError>> + Send {
+        (self.update_item)(context, id, input)
+    }
+
+    fn delete(
+        &self,
+        context: TContext,
+        id: TId,
+    ) -> impl Future<Output = Result<(), AdminError>> + Send {
+        (self.delete_item)(context, id)
+    }
+
+    fn delete_many(
+        &self,
+        context: TContext,
+        ids: Vec<TId>,
+    ) -> impl Future<Output = BulkDeleteResult<TId>> + Send {
+        let delete_items = self.delete_items.clone();
+        let delete_item = self.delete_item.clone();
+
+        async move {
+            // Without a bulk delete callback fall back to deleting one at a time
+            let Some(delete_items) = delete_items else {
+                return delete_each(context, ids, |context, id| (delete_item)(context, id)).await;
+            };
+
+            match (delete_items)(context, ids.clone()).await {
+                Ok(()) => BulkDeleteResult {
+                    deleted: ids,
+                    failed: vec![],
+                },
+                Err(_) => BulkDeleteResult {
+                    deleted: vec![],
+                    failed: ids,
+                },
+            }
+        }
+    }
+
+    fn validate_create(
+        &self,
+        context: TContext,
+        input: &TCreateInput,
+    ) -> impl Future<Output = Result<(), ValidationErrors>> + Send {
+        let validation = self
+            .validate_create
+            .as_ref()
+            .map(|validate_create| (validate_create)(context, input));
+
+        async move {
+            match validation {
+                Some(validation) => validation.await,
+                None => Ok(()),
+            }
+        }
+    }
+
+    fn validate_update(
+        &self,
+        context: TContext,
+        id: &TId,
+        input: &TUpdateInput,
+    ) -> impl Future<Output = Result<(), ValidationErrors>> + Send {
+        let validation = self
+            .validate_update
+            .as_ref()
+            .map(|validate_update| (validate_update)(context, id, input));
+
+        async move {
+            match validation {
+                Some(validation) => validation.await,
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+
+    // The callbacks below never wait on anything so their futures complete on the first poll
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(value) => value,
+            Poll::Pending => panic!("Future was not ready"),
+        }
+    }
+
+    fn resource() -> Resource<(), i32, serde_json::Value, serde_json::Value, serde_json::Value> {
+        Resource {
+            name: "Numbers".to_string(),
+            path: "numbers".to_string(),
+            id_field_id: "id".to_string(),
+            list_items: Arc::new(|_, _| {
+                Box::pin(async {
+                    Ok(ListPage {
+                        items: vec![],
+                        total: None,
+                        next_cursor: None,
+                    })
+                })
+            }),
+            get_item: Arc::new(|_, _| Box::pin(async { Err(AdminError::NotFound) })),
+            create_item: Arc::new(|_, input| Box::pin(async { Ok(input) })),
+            update_item: Arc::new(|_, _, input| Box::pin(async { Ok(input) })),
+            // Only even numbers can be deleted
+            delete_item: Arc::new(|_, id| {
+                Box::pin(async move {
+                    if id % 2 == 0 {
+                        Ok(())
+                    } else {
+                        Err(AdminError::NotFound)
+                    }
+                })
+            }),
+            delete_items: None,
+            count_items: None,
+            validate_create: None,
+            validate_update: None,
+            field_configs: vec![],
+            related_lists: vec![],
+        }
+    }
+
+    #[test]
+    fn resource_delete_many_falls_back_to_delete() {
+        let result = block_on(resource().delete_many((), vec![1, 2, 3, 4]));
+
+        assert_eq!(result.deleted, vec![2, 4]);
+        assert_eq!(result.failed, vec![1, 3]);
+    }
+
+    #[test]
+    fn resource_delete_many_uses_delete_items() {
+        let resource = Resource {
+            delete_items: Some(Arc::new(|_, _| Box::pin(async { Ok(()) }))),
+            ..resource()
+        };
+        let result = block_on(resource.delete_many((), vec![1, 2]));
+
+        assert_eq!(result.deleted, vec![1, 2]);
+        assert!(result.failed.is_empty());
+    }
+
+    #[test]
+    fn resource_count_is_none_without_count_items() {
+        assert_eq!(block_on(resource().count(())).unwrap(), None);
+
+        let resource = Resource {
+            count_items: Some(Arc::new(|_| Box::pin(async { Ok(3) }))),
+            ..resource()
+        };
+        assert_eq!(block_on(resource.count(())).unwrap(), Some(3));
+    }
+}
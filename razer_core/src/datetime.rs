@@ -0,0 +1,372 @@
+use std::sync::Arc;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{AdminError, CreateConfig, FieldConfig, FieldConstraints, ValidationResult};
+use razer_ui::render_date_time_widget;
+
+/// Which of the browser's date and time inputs a field is entered with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateTimeKind {
+    Date,
+    Time,
+    /// A date and time without a timezone, entered and stored as is
+    NaiveDateTime,
+    /// An instant stored in UTC. It's entered in the browser's local time and converted to UTC
+    /// before being submitted.
+    UtcDateTime,
+}
+
+impl DateTimeKind {
+    fn input_type(&self) -> &'static str {
+        match self {
+            DateTimeKind::Date => "date",
+            DateTimeKind::Time => "time",
+            DateTimeKind::NaiveDateTime | DateTimeKind::UtcDateTime => "datetime-local",
+        }
+    }
+
+    fn invalid_message(&self) -> &'static str {
+        match self {
+            DateTimeKind::Date => "Enter a valid date",
+            DateTimeKind::Time => "Enter a valid time",
+            DateTimeKind::NaiveDateTime | DateTimeKind::UtcDateTime => {
+                "Enter a valid date and time"
+            }
+        }
+    }
+}
+
+/// A date or time type which can be entered with the browser's date and time inputs. Values go
+/// through the type itself to convert between the input's value and the type's json, so fields
+/// (de)serialize however the type does. Implemented for the chrono and time types behind the
+/// `chrono` and `time` features.
+pub trait DateTimeField: Serialize + DeserializeOwned {
+    const KIND: DateTimeKind;
+    // Used in the list and detail views unless the field is given its own
+    const DEFAULT_FORMAT: &'static str;
+
+    /// Parses the value of the browser's input. `UtcDateTime` values are RFC 3339 in UTC.
+    fn parse_input_value(value: &str) -> Option<Self>;
+
+    /// Formats the value for the browser's input
+    fn to_input_value(&self) -> String;
+
+    /// Formats the value for display, `None` if the format string is invalid
+    fn format_with(&self, format: &str) -> Option<String>;
+}
+
+fn from_json<T: DateTimeField>(value: &serde_json::Value) -> Option<T> {
+    serde_json::from_value(value.clone()).ok()
+}
+
+/// Formats a date or time field's json using the format string, falling back to the json when it
+/// can't be formatted
+pub fn date_time_display<T: DateTimeField>(
+    format: impl Into<String>,
+) -> Arc<dyn Fn(&serde_json::Value) -> String + Send + Sync> {
+    let format = format.into();
+
+    Arc::new(move |value| {
+        from_json::<T>(value)
+            .and_then(|value| value.format_with(&format))
+            .unwrap_or_else(|| value.to_string())
+    })
+}
+
+impl FieldConfig {
+    /// A date, time or datetime field for any type implementing `DateTimeField`
+    pub fn create_date_time_config<T: DateTimeField>(
+        field_id: String,
+        display_name: String,
+        read_only: bool,
+    ) -> Self {
+        Self {
+            field_id: field_id.clone(),
+            display_name: display_name.clone(),
+            help_text: None,
+            description: None,
+            render: Arc::new(move |value, constraints: &FieldConstraints| {
+                // Values which failed to parse are shown as entered so they can be corrected
+                let input_value = match from_json::<T>(&value) {
+                    Some(value) => Some(value.to_input_value()),
+                    None => value.as_str().map(|value| value.to_string()),
+                };
+
+                Ok::<_, AdminError>(render_date_time_widget(
+                    field_id.clone(),
+                    display_name.clone(),
+                    input_value,
+                    T::KIND.input_type().to_string(),
+                    T::KIND == DateTimeKind::UtcDateTime,
+                    constraints.html_attributes(),
+                ))
+            }),
+            display: Some(date_time_display::<T>(T::DEFAULT_FORMAT)),
+            sortable: false,
+            searchable: false,
+            skip_create: false,
+            nullable: false,
+            relation: None,
+            many: false,
+            constraints: FieldConstraints::default(),
+            filter_config: None,
+            create_config: if read_only {
+                None
+            } else {
+                Some(CreateConfig {
+                    validate: Arc::new(|value| {
+                        if value.is_null() || from_json::<T>(&value).is_some() {
+                            ValidationResult::Valid
+                        } else {
+                            ValidationResult::Invalid(T::KIND.invalid_message().to_string())
+                        }
+                    }),
+                    value_from_form_value: Arc::new(|value| {
+                        let Some(value) = value
+                            .map(|value| value.trim())
+                            .filter(|value| !value.is_empty())
+                        else {
+                            return serde_json::Value::Null;
+                        };
+
+                        T::parse_input_value(value)
+                            .and_then(|value| serde_json::to_value(value).ok())
+                            .unwrap_or_else(|| serde_json::Value::String(value.to_string()))
+                    }),
+                })
+            },
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_fields {
+    use std::fmt::Write;
+
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+
+    use super::{DateTimeField, DateTimeKind};
+
+    // Writing an invalid format string errors rather than panicking like `to_string` would
+    fn format(value: impl std::fmt::Display) -> Option<String> {
+        let mut formatted = String::new();
+        write!(formatted, "{}", value).ok()?;
+        Some(formatted)
+    }
+
+    impl DateTimeField for NaiveDate {
+        const KIND: DateTimeKind = DateTimeKind::Date;
+        const DEFAULT_FORMAT: &'static str = "%Y-%m-%d";
+
+        fn parse_input_value(value: &str) -> Option<Self> {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.format("%Y-%m-%d").to_string()
+        }
+
+        fn format_with(&self, format: &str) -> Option<String> {
+            self::format(self.format(format))
+        }
+    }
+
+    impl DateTimeField for NaiveTime {
+        const KIND: DateTimeKind = DateTimeKind::Time;
+        const DEFAULT_FORMAT: &'static str = "%H:%M:%S";
+
+        // Browsers leave the seconds off when they're zero
+        fn parse_input_value(value: &str) -> Option<Self> {
+            NaiveTime::parse_from_str(value, "%H:%M:%S%.f")
+                .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+                .ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.format("%H:%M:%S").to_string()
+        }
+
+        fn format_with(&self, format: &str) -> Option<String> {
+            self::format(self.format(format))
+        }
+    }
+
+    impl DateTimeField for NaiveDateTime {
+        const KIND: DateTimeKind = DateTimeKind::NaiveDateTime;
+        const DEFAULT_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
+
+        fn parse_input_value(value: &str) -> Option<Self> {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+                .ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.format("%Y-%m-%dT%H:%M:%S").to_string()
+        }
+
+        fn format_with(&self, format: &str) -> Option<String> {
+            self::format(self.format(format))
+        }
+    }
+
+    impl DateTimeField for DateTime<Utc> {
+        const KIND: DateTimeKind = DateTimeKind::UtcDateTime;
+        const DEFAULT_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S UTC";
+
+        fn parse_input_value(value: &str) -> Option<Self> {
+            DateTime::parse_from_rfc3339(value)
+                .map(|value| value.with_timezone(&Utc))
+                .ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.to_rfc3339_opts(SecondsFormat::Secs, true)
+        }
+
+        fn format_with(&self, format: &str) -> Option<String> {
+            self::format(self.format(format))
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_fields {
+    use time::{
+        format_description::{self, well_known::Rfc3339, FormatItem},
+        macros::format_description,
+        Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset,
+    };
+
+    use super::{DateTimeField, DateTimeKind};
+
+    const DATE_INPUT_FORMAT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+    const TIME_INPUT_FORMAT: &[FormatItem<'static>] =
+        format_description!("[hour]:[minute]:[second]");
+    // Browsers leave the seconds off when they're zero
+    const SHORT_TIME_INPUT_FORMAT: &[FormatItem<'static>] = format_description!("[hour]:[minute]");
+    const DATETIME_INPUT_FORMAT: &[FormatItem<'static>] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+    const SHORT_DATETIME_INPUT_FORMAT: &[FormatItem<'static>] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]");
+
+    fn format(format: &str, value: impl FnOnce(&[FormatItem]) -> Option<String>) -> Option<String> {
+        // Version 1 keeps the syntax of the old `format_description::parse`, e.g. `[[` escapes `[`
+        value(&format_description::parse_borrowed::<1>(format).ok()?)
+    }
+
+    impl DateTimeField for Date {
+        const KIND: DateTimeKind = DateTimeKind::Date;
+        const DEFAULT_FORMAT: &'static str = "[year]-[month]-[day]";
+
+        fn parse_input_value(value: &str) -> Option<Self> {
+            Date::parse(value, DATE_INPUT_FORMAT).ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.format(DATE_INPUT_FORMAT).unwrap_or_default()
+        }
+
+        fn format_with(&self, format: &str) -> Option<String> {
+            self::format(format, |items| self.format(items).ok())
+        }
+    }
+
+    impl DateTimeField for Time {
+        const KIND: DateTimeKind = DateTimeKind::Time;
+        const DEFAULT_FORMAT: &'static str = "[hour]:[minute]:[second]";
+
+        fn parse_input_value(value: &str) -> Option<Self> {
+            Time::parse(value, TIME_INPUT_FORMAT)
+                .or_else(|_| Time::parse(value, SHORT_TIME_INPUT_FORMAT))
+                .ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.format(TIME_INPUT_FORMAT).unwrap_or_default()
+        }
+
+        fn format_with(&self, format: &str) -> Option<String> {
+            self::format(format, |items| self.format(items).ok())
+        }
+    }
+
+    impl DateTimeField for PrimitiveDateTime {
+        const KIND: DateTimeKind = DateTimeKind::NaiveDateTime;
+        const DEFAULT_FORMAT: &'static str = "[year]-[month]-[day] [hour]:[minute]:[second]";
+
+        fn parse_input_value(value: &str) -> Option<Self> {
+            PrimitiveDateTime::parse(value, DATETIME_INPUT_FORMAT)
+                .or_else(|_| PrimitiveDateTime::parse(value, SHORT_DATETIME_INPUT_FORMAT))
+                .ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.format(DATETIME_INPUT_FORMAT).unwrap_or_default()
+        }
+
+        fn format_with(&self, format: &str) -> Option<String> {
+            self::format(format, |items| self.format(items).ok())
+        }
+    }
+
+    impl DateTimeField for OffsetDateTime {
+        const KIND: DateTimeKind = DateTimeKind::UtcDateTime;
+        const DEFAULT_FORMAT: &'static str = "[year]-[month]-[day] [hour]:[minute]:[second] UTC";
+
+        fn parse_input_value(value: &str) -> Option<Self> {
+            OffsetDateTime::parse(value, &Rfc3339)
+                .map(|value| value.to_offset(UtcOffset::UTC))
+                .ok()
+        }
+
+        fn to_input_value(&self) -> String {
+            self.to_offset(UtcOffset::UTC)
+                .replace_nanosecond(0)
+                .unwrap_or(*self)
+                .format(&Rfc3339)
+                .unwrap_or_default()
+        }
+
+        // Shown in UTC, as stored
+        fn format_with(&self, format: &str) -> Option<String> {
+            let value = self.to_offset(UtcOffset::UTC);
+            self::format(format, |items| value.format(items).ok())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_time_fields_convert_input_values() {
+        let config = FieldConfig::create_date_time_config::<chrono::DateTime<chrono::Utc>>(
+            "created_at".to_string(),
+            "Created at".to_string(),
+            false,
+        );
+        let create_config = config.create_config.as_ref().unwrap();
+
+        let value =
+            (create_config.value_from_form_value)(Some(&"2024-01-31T12:45:00.000Z".to_string()));
+        assert_eq!(value, serde_json::json!("2024-01-31T12:45:00Z"));
+        assert_eq!(config.validation_error(value.clone()), None);
+        assert_eq!(config.display_value(&value), "2024-01-31 12:45:00 UTC");
+
+        let value = (create_config.value_from_form_value)(Some(&"yesterday".to_string()));
+        assert!(config.validation_error(value).is_some());
+
+        let time = FieldConfig::create_date_time_config::<chrono::NaiveTime>(
+            "opens_at".to_string(),
+            "Opens at".to_string(),
+            false,
+        );
+        let value = (time.create_config.as_ref().unwrap().value_from_form_value)(Some(
+            &"09:30".to_string(),
+        ));
+        assert_eq!(value, serde_json::json!("09:30:00"));
+    }
+}
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use crate::{FieldConfig, NumberConfig};
+
+/// A type which can be used as a field of an admin resource, supplying the default
+/// [`FieldConfig`] for fields of that type. The `AdminResource` derive looks up every field's
+/// config through this trait, so implementing it for your own types lets them be used in models.
+///
+/// ```ignore
+/// struct Email(String);
+///
+/// impl AdminField for Email {
+///     fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+///         let config = FieldConfig::create_text_config(field_id, display_name, read_only);
+///         let constraints = FieldConstraints {
+///             email: true,
+///             ..config.constraints.clone()
+///         };
+///         config.with_constraints(constraints)
+///     }
+/// }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be used as an admin field",
+    label = "no `AdminField` implementation for this type",
+    note = "implement `razer_core::AdminField` for `{Self}` to supply its default `FieldConfig`"
+)]
+pub trait AdminField {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig;
+}
+
+/// The widgets `#[admin(widget = "...")]` can pick, used to look up the field config through
+/// [`AdminWidgetField`]
+pub mod widget {
+    /// `"checkbox"`, for `bool`s
+    pub struct Checkbox;
+
+    /// `"toggle"`, for `bool`s
+    pub struct Toggle;
+
+    /// `"select"`, for `AdminSelect` enums
+    pub struct Select;
+
+    /// `"radio"`, for `AdminSelect` enums
+    pub struct Radio;
+}
+
+/// A field type which can be shown with the widget `W`, for types which can be shown with more
+/// than one, e.g. [`widget::Radio`] for `AdminSelect` enums. This is what
+/// `#[admin(widget = "...")]` uses, so picking a widget the field's type doesn't support fails
+/// to compile.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be shown with the `{W}` widget",
+    label = "`#[admin(widget = \"...\")]` picks a widget this type doesn't support",
+    note = "`checkbox` and `toggle` are for `bool`s, `select` and `radio` for `AdminSelect` enums"
+)]
+pub trait AdminWidgetField<W>: AdminField {
+    fn widget_field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig;
+}
+
+/// A field type whose values can be shown in a format of the model's choosing, e.g. the chrono
+/// and time date and time types. This is what `#[admin(format = "...")]` uses, so using it on
+/// a field of any other type fails to compile.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` doesn't support display formats",
+    label = "`#[admin(format = \"...\")]` can't be used on fields of this type",
+    note = "display formats are supported by the chrono and time date and time types"
+)]
+pub trait AdminFormattedField: AdminField {
+    /// Formats values of the type for the list and detail views using a format string
+    fn display_format(format: &str) -> DisplayFn;
+}
+
+// Formats a field's json value for display
+type DisplayFn = Arc<dyn Fn(&serde_json::Value) -> String + Send + Sync>;
+
+impl AdminField for String {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        FieldConfig::create_text_config(field_id, display_name, read_only)
+    }
+}
+
+impl AdminField for bool {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        FieldConfig::create_boolean_config(field_id, display_name, read_only)
+    }
+}
+
+impl AdminWidgetField<widget::Checkbox> for bool {
+    fn widget_field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        Self::field_config(field_id, display_name, read_only)
+    }
+}
+
+impl AdminWidgetField<widget::Toggle> for bool {
+    fn widget_field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        FieldConfig::create_toggle_config(field_id, display_name, read_only)
+    }
+}
+
+impl<T: AdminField> AdminField for Option<T> {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        T::field_config(field_id, display_name, read_only).nullable()
+    }
+}
+
+impl<W, T: AdminWidgetField<W>> AdminWidgetField<W> for Option<T> {
+    fn widget_field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        T::widget_field_config(field_id, display_name, read_only).nullable()
+    }
+}
+
+impl<T: AdminField> AdminField for Vec<T> {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        T::field_config(field_id, display_name, read_only).many()
+    }
+}
+
+impl<T: AdminFormattedField> AdminFormattedField for Option<T> {
+    fn display_format(format: &str) -> DisplayFn {
+        T::display_format(format)
+    }
+}
+
+impl<T: AdminFormattedField> AdminFormattedField for Vec<T> {
+    fn display_format(format: &str) -> DisplayFn {
+        T::display_format(format)
+    }
+}
+
+macro_rules! impl_admin_field_for_integers {
+    ($($ty:ty),*) => {
+        $(
+            impl_admin_field_for_integers!(@impl $ty, <$ty>::MIN as i128, <$ty>::MAX as i128);
+        )*
+    };
+    (@impl $ty:ty, $min:expr, $max:expr) => {
+        impl AdminField for $ty {
+            fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+                FieldConfig::create_number_config(
+                    field_id,
+                    display_name,
+                    read_only,
+                    NumberConfig::integer($min, $max),
+                )
+            }
+        }
+    };
+}
+
+impl_admin_field_for_integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+// Json numbers hold at most 64 bits, so 128-bit integers take the values which fit in one
+impl_admin_field_for_integers!(@impl i128, i64::MIN.into(), u64::MAX.into());
+impl_admin_field_for_integers!(@impl u128, 0, u64::MAX.into());
+
+impl AdminField for f32 {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        // Larger values would be read as infinity
+        FieldConfig::create_number_config(
+            field_id,
+            display_name,
+            read_only,
+            NumberConfig::float(f32::MIN.into(), f32::MAX.into()),
+        )
+    }
+}
+
+impl AdminField for f64 {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        FieldConfig::create_float_config(field_id, display_name, read_only)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl AdminField for rust_decimal::Decimal {
+    fn field_config(field_id: String, display_name: String, read_only: bool) -> FieldConfig {
+        FieldConfig::create_decimal_config(field_id, display_name, read_only)
+    }
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+macro_rules! impl_admin_field_for_date_times {
+    ($($ty:ty),*) => {
+        $(
+            impl AdminField for $ty {
+                fn field_config(
+                    field_id: String,
+                    display_name: String,
+                    read_only: bool,
+                ) -> FieldConfig {
+                    FieldConfig::create_date_time_config::<$ty>(field_id, display_name, read_only)
+                }
+            }
+
+            impl AdminFormattedField for $ty {
+                fn display_format(format: &str) -> DisplayFn {
+                    crate::date_time_display::<$ty>(format)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "chrono")]
+impl_admin_field_for_date_times!(
+    chrono::NaiveDate,
+    chrono::NaiveTime,
+    chrono::NaiveDateTime,
+    chrono::DateTime<chrono::Utc>
+);
+
+#[cfg(feature = "time")]
+impl_admin_field_for_date_times!(
+    time::Date,
+    time::Time,
+    time::PrimitiveDateTime,
+    time::OffsetDateTime
+);
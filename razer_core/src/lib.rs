@@ -1,224 +1,702 @@
-use razer_ui::{render_checkbox_widget, render_number_input_widget, render_text_widget};
+#![allow(clippy::type_complexity)]
+
+use razer_ui::{
+    empty_field_name, render_checkbox_widget, render_chips_widget, render_nullable_widget,
+    render_number_input_widget, render_text_widget, render_toggle_widget, EMPTY_VALUE_PLACEHOLDER,
+};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{collections::HashMap, future::Future, pin::Pin, str::FromStr, sync::Arc};
 
+mod data_source;
+mod datetime;
+mod field;
+mod list;
+mod number;
+mod relation;
+mod select;
+mod validation;
+
+pub use data_source::*;
+pub use datetime::*;
+pub use field::*;
+pub use list::*;
+pub use number::*;
+pub use relation::*;
+pub use select::*;
+pub use validation::*;
+
 #[derive(Debug)]
 pub enum AdminError {
     NotFound,
     InternalError,
+    Validation(ValidationErrors),
+    Unauthorized,
+    Forbidden,
+    // The request clashes with the current state of the data, e.g. a duplicate key
+    Conflict(String),
+    BadRequest(String),
+    // Any other failure. The message is shown to the user while the source is kept for logging
+    Custom {
+        message: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl AdminError {
+    pub fn custom(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        AdminError::Custom {
+            message: message.into(),
+            source: source.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for AdminError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdminError::NotFound => write!(f, "Not found"),
+            AdminError::InternalError => write!(f, "Internal error"),
+            AdminError::Validation(_) => write!(f, "Validation failed"),
+            AdminError::Unauthorized => write!(f, "Unauthorized"),
+            AdminError::Forbidden => write!(f, "Forbidden"),
+            AdminError::Conflict(message)
+            | AdminError::BadRequest(message)
+            | AdminError::Custom { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AdminError::Custom { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
 }
 
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
 // This should be part of razer (not core) as users will depend on it directly
-pub struct Resource<TContext, TId: ToString, TItem: Serialize, TCreateInput: DeserializeOwned> {
+pub struct Resource<
+    TContext,
+    TId: ToString,
+    TItem: Serialize,
+    TCreateInput: DeserializeOwned,
+    TUpdateInput: DeserializeOwned,
+> {
     pub name: String,
     pub path: String,
     pub id_field_id: String,
 
     // TODO Can this be box again??
     pub list_items: Arc<
-        dyn Fn(TContext) -> Pin<Box<dyn Future<Output = Result<Vec<TItem>, AdminError>> + Send>>
-            + Send
-            + Sync,
+        dyn Fn(TContext, ListQuery) -> BoxFuture<Result<ListPage<TItem>, AdminError>> + Send + Sync,
     >,
-    pub get_item: Arc<
-        dyn Fn(TContext, TId) -> Pin<Box<dyn Future<Output = Result<TItem, AdminError>> + Send>>
-            + Send
-            + Sync,
+    pub get_item: Arc<dyn Fn(TContext, TId) -> BoxFuture<Result<TItem, AdminError>> + Send + Sync>,
+    pub create_item:
+        Arc<dyn Fn(TContext, TCreateInput) -> BoxFuture<Result<TItem, AdminError>> + Send + Sync>,
+    pub update_item: Arc<
+        dyn Fn(TContext, TId, TUpdateInput) -> BoxFuture<Result<TItem, AdminError>> + Send + Sync,
     >,
-    pub create_item: Arc<
-        dyn Fn(
-                TContext,
-                TCreateInput,
-            ) -> Pin<Box<dyn Future<Output = Result<TItem, AdminError>> + Send>>
-            + Send
-            + Sync,
+    pub delete_item: Arc<dyn Fn(TContext, TId) -> BoxFuture<Result<(), AdminError>> + Send + Sync>,
+    // Optional callback to delete many items at once. If not provided bulk deletes fall back to
+    // calling `delete_item` once per id
+    pub delete_items:
+        Option<Arc<dyn Fn(TContext, Vec<TId>) -> BoxFuture<Result<(), AdminError>> + Send + Sync>>,
+    // Optional callback to count the items, shown on the admin's home page. Without it no count
+    // is shown
+    pub count_items:
+        Option<Arc<dyn Fn(TContext) -> BoxFuture<Result<u64, AdminError>> + Send + Sync>>,
+    // Optional checks on the whole input, run once every field has passed its own validation.
+    // Use these for rules involving several fields or which need the data source, e.g. checking
+    // an email is unique. The update check is given the id so an item can be excluded from checks
+    // against itself
+    pub validate_create: Option<
+        Arc<
+            dyn Fn(TContext, &TCreateInput) -> BoxFuture<Result<(), ValidationErrors>>
+                + Send
+                + Sync,
+        >,
+    >,
+    pub validate_update: Option<
+        Arc<
+            dyn Fn(TContext, &TId, &TUpdateInput) -> BoxFuture<Result<(), ValidationErrors>>
+                + Send
+                + Sync,
+        >,
     >,
 
     // Field name to config
     pub field_configs: Vec<FieldConfig>,
+    // Lists of related items shown on an item's detail page
+    pub related_lists: Vec<RelatedList>,
 }
 
 pub struct JsonResource<TContext> {
     pub id_field_id: String,
+    pub label_field_id: String,
     pub name: String,
+    pub description: Option<String>,
     pub path: String,
 
     pub list_items: Arc<
-        dyn Fn(
-                TContext,
-            )
-                -> Pin<Box<dyn Future<Output = Result<Vec<JsonResourceItem>, AdminError>> + Send>>
+        dyn Fn(TContext, ListQuery) -> BoxFuture<Result<ListPage<JsonResourceItem>, AdminError>>
             + Send
             + Sync,
     >,
+    pub count_items:
+        Arc<dyn Fn(TContext) -> BoxFuture<Result<Option<u64>, AdminError>> + Send + Sync>,
     pub get_item: Arc<
-        dyn Fn(
-                TContext,
-                String,
-            )
-                -> Pin<Box<dyn Future<Output = Result<JsonResourceItem, AdminError>> + Send>>
-            + Send
-            + Sync,
-    >,
-    pub create_item: Arc<
-        dyn Fn(TContext, &[u8]) -> Pin<Box<dyn Future<Output = Result<(), AdminError>> + Send>>
-            + Send
-            + Sync,
+        dyn Fn(TContext, String) -> BoxFuture<Result<JsonResourceItem, AdminError>> + Send + Sync,
     >,
+    pub create_item:
+        Arc<dyn Fn(TContext, &[u8]) -> BoxFuture<Result<(), AdminError>> + Send + Sync>,
+    pub update_item:
+        Arc<dyn Fn(TContext, String, &[u8]) -> BoxFuture<Result<(), AdminError>> + Send + Sync>,
+    pub delete_item:
+        Arc<dyn Fn(TContext, String) -> BoxFuture<Result<(), AdminError>> + Send + Sync>,
+    pub delete_items:
+        Arc<dyn Fn(TContext, Vec<String>) -> BoxFuture<BulkDeleteResult> + Send + Sync>,
 
     // Field name to config
     pub field_configs: Vec<FieldConfig>,
+    pub related_lists: Vec<RelatedList>,
+    // The resource this one is served under, for nested resources
+    pub parent: Option<ParentConfig>,
+}
+
+// Derived `Clone` would need `TContext: Clone` even though only the callbacks use it
+impl<TContext> Clone for JsonResource<TContext> {
+    fn clone(&self) -> Self {
+        Self {
+            id_field_id: self.id_field_id.clone(),
+            label_field_id: self.label_field_id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            path: self.path.clone(),
+            list_items: self.list_items.clone(),
+            count_items: self.count_items.clone(),
+            get_item: self.get_item.clone(),
+            create_item: self.create_item.clone(),
+            update_item: self.update_item.clone(),
+            delete_item: self.delete_item.clone(),
+            delete_items: self.delete_items.clone(),
+            field_configs: self.field_configs.clone(),
+            related_lists: self.related_lists.clone(),
+            parent: self.parent.clone(),
+        }
+    }
+}
+
+impl<TContext> JsonResource<TContext> {
+    /// The url the resource's items are listed at. Nested resources are listed under one of their
+    /// parent's items, so need the parent item's id.
+    pub fn list_url(&self, parent_id: Option<&str>) -> String {
+        match (&self.parent, parent_id) {
+            (Some(parent), Some(parent_id)) => parent.list_url(&self.path, parent_id),
+            _ => format!("/admin/{}", self.path),
+        }
+    }
+
+    /// The url of an item's detail page. Items of nested resources are found under the parent
+    /// item their parent field points at.
+    pub fn item_url(&self, item: &JsonResourceItem) -> Option<String> {
+        let parent_id = match &self.parent {
+            Some(parent) => Some(item.get(&parent.field_id).and_then(id_from_json)?),
+            None => None,
+        };
+
+        Some(format!(
+            "{}/{}",
+            self.list_url(parent_id.as_deref()),
+            self.item_id(item)?
+        ))
+    }
+
+    /// The id of an item, as used in urls
+    pub fn item_id(&self, item: &JsonResourceItem) -> Option<String> {
+        item.get(&self.id_field_id).and_then(id_from_json)
+    }
+
+    /// The text an item is referred to by, its label field's value formatted for display. Items
+    /// with no label are referred to by their id.
+    pub fn item_label(&self, item: &JsonResourceItem) -> String {
+        let display = self
+            .field_configs
+            .iter()
+            .find(|config| config.field_id == self.label_field_id)
+            .and_then(|config| config.display.clone());
+        // Strings are shown without the quotes json would add
+        let label = item
+            .get(&self.label_field_id)
+            .and_then(|value| match &display {
+                Some(display) if !value.is_null() => Some(display(value)),
+                _ => id_from_json(value),
+            });
+
+        label
+            .or_else(|| self.item_id(item))
+            .unwrap_or_else(|| EMPTY_VALUE_PLACEHOLDER.to_string())
+    }
+}
+
+/// The outcome of a bulk delete, split into the ids which were deleted and the ids which failed
+#[derive(Debug)]
+pub struct BulkDeleteResult<TId = String> {
+    pub deleted: Vec<TId>,
+    pub failed: Vec<TId>,
+}
+
+impl<TId> Default for BulkDeleteResult<TId> {
+    fn default() -> Self {
+        Self {
+            deleted: vec![],
+            failed: vec![],
+        }
+    }
+}
+
+/// Builds the json value for an input type from submitted form data, using each field's
+/// `value_from_form_value`. Fields without a create config (read only fields) are skipped, and
+/// nullable fields marked as empty are null.
+pub fn json_from_form_data(field_configs: &[FieldConfig], data: &[u8]) -> JsonResourceItem {
+    let form_data = form_urlencoded::parse(data);
+    let form_data_map: HashMap<String, String> = form_data.into_owned().collect();
+
+    field_configs
+        .iter()
+        .fold(serde_json::Map::new(), |mut map, field_config| {
+            let field_id = &field_config.field_id;
+
+            if let Some(create_config) = &field_config.create_config {
+                let is_empty = field_config.nullable
+                    && form_data_map.contains_key(&empty_field_name(field_id));
+                let field_value = if is_empty {
+                    serde_json::Value::Null
+                } else {
+                    (create_config.value_from_form_value)(form_data_map.get(field_id))
+                };
+                map.insert(field_id.clone(), field_value);
+            }
+
+            map
+        })
 }
 
-impl<
-        TContext: Send + 'static,
-        TId: ToString + FromStr + 'static,
-        TItem: Serialize + 'static,
-        TCreateInput: DeserializeOwned + 'static + Send,
-    > From<Resource<TContext, TId, TItem, TCreateInput>> for JsonResource<TContext>
-{
-    fn from(value: Resource<TContext, TId, TItem, TCreateInput>) -> Self {
-        let list_items_closure = value.list_items;
-        let get_item_closure = value.get_item;
-        let create_item_closure = value.create_item;
-        let field_configs = value.field_configs;
+/// Reads submitted form data into json, failing with the validation errors of any invalid fields
+fn validated_json_from_form_data(
+    field_configs: &[FieldConfig],
+    data: &[u8],
+) -> Result<serde_json::Value, AdminError> {
+    let json_data = json_from_form_data(field_configs, data);
+
+    let errors = validate_fields(field_configs, &json_data);
+    if !errors.is_empty() {
+        return Err(AdminError::Validation(errors));
+    }
+
+    Ok(serde_json::Value::Object(json_data))
+}
+
+/// Deserializes an input type from the json built from form data. Failures are reported as
+/// validation errors against the field they happened in, or against the whole form when they
+/// can't be tied to a field (e.g. a missing field).
+fn input_from_json<TInput: DeserializeOwned>(
+    json_data: serde_json::Value,
+) -> Result<TInput, AdminError> {
+    serde_path_to_error::deserialize(json_data).map_err(|e| {
+        let mut errors = ValidationErrors::default();
+        let message = e.inner().to_string();
+
+        match e.path().iter().next() {
+            Some(serde_path_to_error::Segment::Map { key }) => {
+                errors.add_field_error(key.clone(), message)
+            }
+            _ => errors.add_form_error(message),
+        }
+
+        AdminError::Validation(errors)
+    })
+}
+
+impl<TContext: Clone + Send + 'static> JsonResource<TContext> {
+    /// Erases the types of a data source so resources with different item types can be served
+    /// together. Items are converted to and from json at the edges.
+    pub fn from_data_source<TDataSource: AdminDataSource<TContext>>(
+        data_source: TDataSource,
+    ) -> Self {
+        let data_source = Arc::new(data_source);
+        let field_configs = data_source.field_configs();
+        let create_field_configs: Vec<FieldConfig> = field_configs
+            .iter()
+            .filter(|field_config| field_config.is_creatable())
+            .cloned()
+            .collect();
+        // TODO Having to clone field configs here is a bit sad - its because we have to move
+        // field configs into the update closure. Would be nice to understand this and see if
+        // there is an alternative
+        let update_field_configs = field_configs.clone();
 
         JsonResource {
-            name: value.name,
-            path: value.path,
-            id_field_id: value.id_field_id,
-            // TODO Having to clone field configs here is a bit sad - its because we have to move
-            // field configs into the create closure. Would be nice to understand this and see if
-            // there is an alternative
-            field_configs: field_configs.clone(),
-            list_items: Arc::new(move |context| {
-                let list_items_closure = Arc::clone(&list_items_closure);
+            name: data_source.name(),
+            description: data_source.description(),
+            path: data_source.path(),
+            id_field_id: data_source.id_field_id(),
+            label_field_id: data_source.label_field_id(),
+            related_lists: data_source.related_lists(),
+            parent: None,
+            field_configs,
+            list_items: Arc::new({
+                let data_source = Arc::clone(&data_source);
 
-                Box::pin(async move {
-                    let items = (list_items_closure)(context).await?;
-
-                    let json_items = items
-                        .into_iter()
-                        .map(|item| {
-                            serde_json::to_value(item)
-                                .unwrap()
-                                .as_object()
-                                .unwrap()
-                                .clone()
+                move |context, query| {
+                    let data_source = Arc::clone(&data_source);
+
+                    Box::pin(async move {
+                        let page = data_source.list(context, query).await?;
+
+                        let json_items = page
+                            .items
+                            .into_iter()
+                            .map(|item| match serde_json::to_value(item) {
+                                Ok(serde_json::Value::Object(map)) => Ok(map),
+                                _ => Err(AdminError::InternalError),
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                        Ok(ListPage {
+                            items: json_items,
+                            total: page.total,
+                            next_cursor: page.next_cursor,
                         })
-                        .collect::<Vec<_>>();
-                    Ok(json_items)
+                    })
+                }
+            }),
+            count_items: Arc::new({
+                let data_source = Arc::clone(&data_source);
+
+                move |context| {
+                    let data_source = Arc::clone(&data_source);
+
+                    Box::pin(async move { data_source.count(context).await })
+                }
+            }),
+            get_item: Arc::new({
+                let data_source = Arc::clone(&data_source);
+
+                move |context, id| {
+                    let data_source = Arc::clone(&data_source);
+
+                    Box::pin(async move {
+                        let id =
+                            TDataSource::Id::from_str(&id).map_err(|_| AdminError::NotFound)?;
+                        let item = data_source.get(context, id).await?;
+                        let value =
+                            serde_json::to_value(item).map_err(|_| AdminError::InternalError)?;
+                        match value {
+                            serde_json::Value::Object(map) => Ok(map),
+                            _ => Err(AdminError::NotFound),
+                        }
+                    })
+                }
+            }),
+            create_item: Arc::new({
+                let data_source = Arc::clone(&data_source);
+
+                move |context, data| {
+                    let data_source = Arc::clone(&data_source);
+
+                    // Nothing is created unless every field is valid
+                    let parse_result = validated_json_from_form_data(&create_field_configs, data)
+                        .and_then(input_from_json::<TDataSource::CreateInput>);
+
+                    Box::pin(async move {
+                        let item_to_insert = parse_result?;
+
+                        data_source
+                            .validate_create(context.clone(), &item_to_insert)
+                            .await
+                            .map_err(AdminError::Validation)?;
+
+                        data_source.create(context, item_to_insert).await?;
+                        Ok(())
+                    })
+                }
+            }),
+            update_item: Arc::new({
+                let data_source = Arc::clone(&data_source);
+
+                move |context, id, data| {
+                    let data_source = Arc::clone(&data_source);
+
+                    let id = TDataSource::Id::from_str(&id).map_err(|_| AdminError::NotFound);
+                    let parse_result = validated_json_from_form_data(&update_field_configs, data)
+                        .and_then(input_from_json::<TDataSource::UpdateInput>);
+
+                    Box::pin(async move {
+                        let item_to_update = parse_result?;
+                        let id = id?;
+
+                        data_source
+                            .validate_update(context.clone(), &id, &item_to_update)
+                            .await
+                            .map_err(AdminError::Validation)?;
+
+                        data_source.update(context, id, item_to_update).await?;
+                        Ok(())
+                    })
+                }
+            }),
+            delete_item: Arc::new({
+                let data_source = Arc::clone(&data_source);
+
+                move |context, id| {
+                    let data_source = Arc::clone(&data_source);
+
+                    Box::pin(async move {
+                        let id =
+                            TDataSource::Id::from_str(&id).map_err(|_| AdminError::NotFound)?;
+                        data_source.delete(context, id).await
+                    })
+                }
+            }),
+            delete_items: Arc::new(move |context, ids| {
+                let data_source = Arc::clone(&data_source);
+
+                Box::pin(async move {
+                    let mut result = BulkDeleteResult::default();
+
+                    // Ids which can't be parsed can never be deleted so are reported as failures
+                    let mut parsed_ids = vec![];
+                    for id in ids {
+                        match TDataSource::Id::from_str(&id) {
+                            Ok(parsed_id) => parsed_ids.push(parsed_id),
+                            Err(_) => result.failed.push(id),
+                        }
+                    }
+
+                    let deleted = data_source.delete_many(context, parsed_ids).await;
+                    result
+                        .deleted
+                        .extend(deleted.deleted.iter().map(ToString::to_string));
+                    result
+                        .failed
+                        .extend(deleted.failed.iter().map(ToString::to_string));
+
+                    result
                 })
             }),
-            get_item: Arc::new(move |context, id| {
-                let get_item_closure = Arc::clone(&get_item_closure);
+        }
+    }
+}
+
+impl<TContext: Clone + Send + 'static> JsonResource<TContext> {
+    /// The resource as served under one of its parent's items, for nested resources. The parent
+    /// id is added to list queries as a filter on the parent field and set as the parent field of
+    /// created and updated items, while items of other parents can't be found. The parent field
+    /// is left out of the fields, as it always holds the parent id.
+    ///
+    /// Resources which aren't nested are returned as they are.
+    pub fn for_parent(&self, parent_id: &str) -> Self {
+        let Some(parent) = self.parent.clone() else {
+            return self.clone();
+        };
+        let parent_id = parent_id.to_string();
+
+        // Looks up an item, failing as not found if it belongs to another parent
+        let get_item: Arc<
+            dyn Fn(TContext, String) -> BoxFuture<Result<JsonResourceItem, AdminError>>
+                + Send
+                + Sync,
+        > = Arc::new({
+            let get_item = self.get_item.clone();
+            let parent = parent.clone();
+            let parent_id = parent_id.clone();
+
+            move |context, id| {
+                let item = (*get_item)(context, id);
+                let parent = parent.clone();
+                let parent_id = parent_id.clone();
 
                 Box::pin(async move {
-                    let id = TId::from_str(&id).map_err(|_| AdminError::InternalError)?;
-                    let item = (get_item_closure)(context, id).await?;
-                    let value =
-                        serde_json::to_value(item).map_err(|_| AdminError::InternalError)?;
-                    match value {
-                        serde_json::Value::Object(map) => Ok(map),
-                        _ => Err(AdminError::NotFound),
+                    let item = item.await?;
+                    if !parent.is_parent_of(&item, &parent_id) {
+                        return Err(AdminError::NotFound);
                     }
+                    Ok(item)
                 })
+            }
+        });
+
+        JsonResource {
+            field_configs: self
+                .field_configs
+                .iter()
+                .filter(|config| config.field_id != parent.field_id)
+                .cloned()
+                .collect(),
+            list_items: Arc::new({
+                let list_items = self.list_items.clone();
+                let parent = parent.clone();
+                let parent_id = parent_id.clone();
+
+                move |context, mut query| {
+                    // Replace any filter on the parent field so other parents' items can't be
+                    // listed
+                    query
+                        .filters
+                        .retain(|filter| filter.field_id != parent.field_id);
+                    query.filters.push(parent.list_filter(&parent_id));
+                    (*list_items)(context, query)
+                }
             }),
-            create_item: Arc::new(move |context, data| {
-                let create_item_closure = Arc::clone(&create_item_closure);
-                // let item_to_insert: Result<TCreateInput, _> = serde_urlencoded::from_bytes(&data);
-
-                let form_data = form_urlencoded::parse(&data);
-                let form_data_map: HashMap<String, String> = form_data.into_owned().collect();
-                dbg!(&form_data_map);
-
-                let json_data =
-                    field_configs
-                        .iter()
-                        .fold(serde_json::Map::new(), |mut map, field_config| {
-                            let field_id = &field_config.field_id;
-
-                            if let Some(create_config) = &field_config.create_config {
-                                let field_value =
-                                    (create_config.value_from_form_value)(form_data_map.get(field_id));
-                                map.insert(field_id.clone(), field_value);
-                            }
+            // The count is of every parent's items, which says nothing about this parent's
+            count_items: Arc::new(|_| Box::pin(async { Ok(None) })),
+            create_item: Arc::new({
+                let create_item = self.create_item.clone();
+                let parent = parent.clone();
+                let parent_id = parent_id.clone();
 
-                            return map;
-                        });
+                move |context, data| (*create_item)(context, &parent.form_data(data, &parent_id))
+            }),
+            update_item: Arc::new({
+                let get_item = get_item.clone();
+                let update_item = self.update_item.clone();
+                let parent = parent.clone();
+                let parent_id = parent_id.clone();
 
-                dbg!(&json_data);
+                move |context, id, data| {
+                    let get_item = get_item.clone();
+                    let update_item = update_item.clone();
+                    let data = parent.form_data(data, &parent_id);
 
-                // let deserializer = serde_urlencoded::Deserializer::new();
-                // let parse_result: Result<TCreateInput, _> = serde_path_to_error::deserialize(deserializer);
+                    Box::pin(async move {
+                        (*get_item)(context.clone(), id.clone()).await?;
+                        (*update_item)(context, id, &data).await
+                    })
+                }
+            }),
+            delete_item: Arc::new({
+                let get_item = get_item.clone();
+                let delete_item = self.delete_item.clone();
 
-                let parse_result = serde_json::from_value(serde_json::Value::Object(json_data));
+                move |context, id| {
+                    let get_item = get_item.clone();
+                    let delete_item = delete_item.clone();
 
-                match parse_result {
-                    Ok(item_to_insert) => Box::pin(async move {
-                        (create_item_closure)(context, item_to_insert).await?;
-                        Ok(())
-                    }),
-                    Err(e) => {
-                        dbg!(e);
-                        todo!()
-                    }
+                    Box::pin(async move {
+                        (*get_item)(context.clone(), id.clone()).await?;
+                        (*delete_item)(context, id).await
+                    })
                 }
+            }),
+            delete_items: Arc::new({
+                let get_item = get_item.clone();
+                let delete_items = self.delete_items.clone();
 
-                // dbg!(item_to_insert);
+                move |context, ids| {
+                    let get_item = get_item.clone();
+                    let delete_items = delete_items.clone();
 
-                // // serde_urlencoded::Deserializer::new(form_urlencoded::parse(&data));
+                    Box::pin(async move {
+                        // Items of other parents are reported as failures without being deleted
+                        let mut result = BulkDeleteResult::default();
+                        let mut parent_ids = vec![];
+                        for id in ids {
+                            match (*get_item)(context.clone(), id.clone()).await {
+                                Ok(_) => parent_ids.push(id),
+                                Err(_) => result.failed.push(id),
+                            }
+                        }
 
-                // dbg!("Creating with: {}", &data);
+                        let deleted = (*delete_items)(context, parent_ids).await;
+                        result.deleted.extend(deleted.deleted);
+                        result.failed.extend(deleted.failed);
+                        result
+                    })
+                }
             }),
+            get_item,
+            ..self.clone()
         }
     }
 }
 
-type JsonResourceItem = serde_json::Map<String, serde_json::Value>;
+pub type JsonResourceItem = serde_json::Map<String, serde_json::Value>;
 
-pub struct Theme {}
+/// A widget on the admin's home page, e.g. a stat tile or a list of recent items. It's given the
+/// admin context and renders to html, see `razer_ui::render_stat_tile` and
+/// `razer_ui::render_recent_items`.
+pub type DashboardWidget<TContext> =
+    Arc<dyn Fn(TContext) -> BoxFuture<Result<String, AdminError>> + Send + Sync>;
 
-impl Default for Theme {
-    fn default() -> Self {
-        Self {}
-    }
-}
+#[derive(Default)]
+pub struct Theme {}
 
 pub struct Admin<TContext> {
     pub title: String,
     pub theme: Theme,
     pub resources: Vec<JsonResource<TContext>>,
+    // Shown on the home page, in the order they were added
+    pub widgets: Vec<DashboardWidget<TContext>>,
     pub context: TContext,
 }
 
-impl<TContext: Send + 'static> Admin<TContext> {
+impl<TContext: Clone + Send + 'static> Admin<TContext> {
     pub fn new(context: TContext) -> Self {
         Admin {
             title: "Razer admin".to_string(),
             theme: Theme::default(),
             resources: vec![],
+            widgets: vec![],
             context,
         }
     }
 
-    // TODO Is sta
-    pub fn register<
-        TId: ToString + FromStr + 'static,
-        TItem: Serialize + 'static,
-        TCreateInput: DeserializeOwned + 'static + Send,
-    >(
+    /// Adds a resource to the admin, backed by any data source e.g. a closure based [`Resource`]
+    pub fn register<TDataSource: AdminDataSource<TContext>>(
         mut self,
-        resource: Resource<TContext, TId, TItem, TCreateInput>,
+        data_source: TDataSource,
     ) -> Self {
-        self.resources.push(resource.into());
-        return self;
+        self.resources
+            .push(JsonResource::from_data_source(data_source));
+        self
     }
-}
 
-pub enum ValidationResult {
-    Invalid(String),
-    Valid,
+    /// Adds a widget to the home page, rendered each time the page is loaded
+    pub fn with_dashboard_widget(
+        mut self,
+        widget: impl Fn(TContext) -> BoxFuture<Result<String, AdminError>> + Send + Sync + 'static,
+    ) -> Self {
+        self.widgets.push(Arc::new(widget));
+        self
+    }
+
+    /// Adds a resource nested under the items of another, e.g. a customer's addresses served
+    /// under `/admin/customers/{id}/addresses` rather than on their own. `field_id` is the nested
+    /// resource's relation field pointing at the parent resource, which the parent's id is passed
+    /// to the data source in: lists are filtered by it and items are created with it. See
+    /// [`JsonResource::for_parent`].
+    ///
+    /// The nested items are listed on the detail page of their parent item.
+    pub fn register_nested<TDataSource: AdminDataSource<TContext>>(
+        mut self,
+        parent_path: impl Into<String>,
+        field_id: impl Into<String>,
+        data_source: TDataSource,
+    ) -> Self {
+        self.resources.push(JsonResource {
+            parent: Some(ParentConfig {
+                resource_path: parent_path.into(),
+                field_id: field_id.into(),
+            }),
+            ..JsonResource::from_data_source(data_source)
+        });
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -233,32 +711,217 @@ pub struct FieldConfig {
     pub display_name: String,
     pub description: Option<String>,
     pub help_text: Option<String>,
-    pub render: Arc<dyn Fn(serde_json::Value) -> Result<String, AdminError> + Send + Sync>,
+    // Renders the field's widget for a value. The constraints are passed in so they can be added
+    // to the widget as HTML constraint attributes
+    pub render: Arc<
+        dyn Fn(serde_json::Value, &FieldConstraints) -> Result<String, AdminError> + Send + Sync,
+    >,
+    // Formats the value as text for the list and detail views. The json value is shown when there
+    // isn't one
+    pub display: Option<Arc<dyn Fn(&serde_json::Value) -> String + Send + Sync>>,
+    // Whether the list view can be sorted by this field. Sorting is done by the data source so
+    // only enable this for fields the data source knows how to order by
+    pub sortable: bool,
+    // The filter offered for this field in the list view, if any. As with sorting the data source
+    // is responsible for applying the filters passed in the list query
+    pub filter_config: Option<FilterConfig>,
+    // Whether the list view search term should be matched against this field
+    pub searchable: bool,
+    // Whether the field is left out when creating an item, e.g. a field which only makes sense
+    // once the item exists. It can still be edited afterwards
+    pub skip_create: bool,
+    // Whether the field can be left empty, e.g. `Option` fields. Empty fields are null in the json
+    pub nullable: bool,
+    // The resource the field's value is the id of, if it's a relation. See `with_relation`
+    pub relation: Option<RelationConfig>,
+    // Whether the field holds a list of values, e.g. `Vec` fields. See `many`
+    pub many: bool,
+    pub constraints: FieldConstraints,
 
     pub create_config: Option<CreateConfig>,
 }
 
 impl FieldConfig {
+    /// Adds declarative constraints to the field. They are checked before the field's own
+    /// validator runs.
+    pub fn with_constraints(self, constraints: FieldConstraints) -> Self {
+        let validate_constraints = constraints.validator();
+
+        Self {
+            create_config: self.create_config.map(|create_config| {
+                let validate = create_config.validate;
+
+                CreateConfig {
+                    validate: Arc::new(move |value| match validate_constraints(&value) {
+                        ValidationResult::Valid => validate(value),
+                        invalid => invalid,
+                    }),
+                    ..create_config
+                }
+            }),
+            constraints,
+            ..self
+        }
+    }
+
+    /// Whether the field is shown when creating an item
+    pub fn is_creatable(&self) -> bool {
+        self.create_config.is_some() && !self.skip_create
+    }
+
+    /// Allows the field to be left empty, which it is when its "Empty" checkbox is checked. Null
+    /// values skip the field's own validator, so they're valid unless the field is `required`.
+    pub fn nullable(self) -> Self {
+        Self {
+            nullable: true,
+            create_config: self.create_config.map(|create_config| {
+                let validate = create_config.validate;
+
+                CreateConfig {
+                    validate: Arc::new(move |value| match value {
+                        serde_json::Value::Null => ValidationResult::Valid,
+                        value => validate(value),
+                    }),
+                    value_from_form_value: create_config.value_from_form_value,
+                }
+            }),
+            ..self
+        }
+    }
+
+    /// Makes the field hold a list of values of its type, e.g. `Vec` fields. The values are
+    /// entered as chips and submitted as a json array of strings, each of which is read and
+    /// validated as the field's own type.
+    pub fn many(self) -> Self {
+        let field_id = self.field_id.clone();
+        let display_name = self.display_name.clone();
+
+        Self {
+            many: true,
+            render: Arc::new(move |value, constraints| {
+                Ok(render_chips_widget(
+                    field_id.clone(),
+                    display_name.clone(),
+                    chip_values(&value, None),
+                    None,
+                    constraints.html_attributes(),
+                ))
+            }),
+            filter_config: None,
+            create_config: self.create_config.map(|create_config| {
+                let validate = create_config.validate;
+                let value_from_form_value = create_config.value_from_form_value;
+
+                CreateConfig {
+                    validate: Arc::new(move |value| match value {
+                        serde_json::Value::Array(values) => values
+                            .into_iter()
+                            .map(|value| validate(value))
+                            .find(|result| *result != ValidationResult::Valid)
+                            .unwrap_or(ValidationResult::Valid),
+                        _ => ValidationResult::Invalid("Enter a list of values".to_string()),
+                    }),
+                    value_from_form_value: Arc::new(move |value| {
+                        let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+                            return serde_json::Value::Array(vec![]);
+                        };
+
+                        // Anything else is kept as submitted for the validator to report
+                        match serde_json::from_str::<Vec<String>>(value) {
+                            Ok(values) => serde_json::Value::Array(
+                                values
+                                    .iter()
+                                    .map(|value| value_from_form_value(Some(value)))
+                                    .collect(),
+                            ),
+                            Err(_) => serde_json::Value::String(value.clone()),
+                        }
+                    }),
+                }
+            }),
+            ..self
+        }
+    }
+
+    /// Formats the field's value for the list and detail views
+    pub fn display_value(&self, value: &serde_json::Value) -> String {
+        match &self.display {
+            Some(display) => display(value),
+            None => value.to_string(),
+        }
+    }
+
+    pub fn with_display(
+        self,
+        display: impl Fn(&serde_json::Value) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            display: Some(Arc::new(display)),
+            ..self
+        }
+    }
+
+    /// Shows the field's values in the given format, for field types which support one, e.g.
+    /// `"%d %B %Y"` for chrono dates. See [`AdminFormattedField`].
+    pub fn with_display_format<T: AdminFormattedField>(self, format: &str) -> Self {
+        Self {
+            display: Some(T::display_format(format)),
+            ..self
+        }
+    }
+
+    pub fn render_widget(&self, value: serde_json::Value) -> Result<String, AdminError> {
+        if self.nullable {
+            let is_empty = value.is_null();
+            let widget = (self.render)(value, &self.constraints)?;
+            return Ok(render_nullable_widget(
+                self.field_id.clone(),
+                widget,
+                is_empty,
+            ));
+        }
+
+        (self.render)(value, &self.constraints)
+    }
+
+    /// Runs the field's validator against a value. Read only fields are never invalid.
+    pub fn validation_error(&self, value: serde_json::Value) -> Option<String> {
+        match (self.create_config.as_ref()?.validate)(value) {
+            ValidationResult::Invalid(message) => Some(message),
+            ValidationResult::Valid => None,
+        }
+    }
+
     pub fn create_text_config(field_id: String, display_name: String, read_only: bool) -> Self {
         Self {
             field_id: field_id.clone(),
             display_name: display_name.clone(),
             help_text: None,
             description: None,
-            render: Arc::new(move |value| {
+            render: Arc::new(move |value, constraints| {
                 Ok(render_text_widget(
                     field_id.clone(),
                     display_name.clone(),
                     value,
+                    constraints.html_attributes(),
                 ))
             }),
+            display: None,
+            sortable: false,
+            searchable: false,
+            skip_create: false,
+            nullable: false,
+            relation: None,
+            many: false,
+            constraints: FieldConstraints::default(),
+            filter_config: Some(FilterConfig::Text),
             create_config: if read_only {
                 None
             } else {
                 Some(CreateConfig {
-                    validate: Arc::new(|value| ValidationResult::Valid),
+                    validate: Arc::new(|_| ValidationResult::Valid),
                     value_from_form_value: Arc::new(|value| {
-                        serde_json::Value::String(value.unwrap().clone())
+                        serde_json::Value::String(value.cloned().unwrap_or_default())
                     }),
                 })
             },
@@ -271,18 +934,28 @@ impl FieldConfig {
             display_name: display_name.clone(),
             help_text: None,
             description: None,
-            render: Arc::new(move |value| {
+            render: Arc::new(move |value, constraints| {
                 Ok(render_checkbox_widget(
                     field_id.clone(),
                     display_name.clone(),
                     value,
+                    constraints.html_attributes(),
                 ))
             }),
+            display: None,
+            sortable: false,
+            searchable: false,
+            skip_create: false,
+            nullable: false,
+            relation: None,
+            many: false,
+            constraints: FieldConstraints::default(),
+            filter_config: Some(FilterConfig::Boolean),
             create_config: if read_only {
                 None
             } else {
                 Some(CreateConfig {
-                    validate: Arc::new(|value| ValidationResult::Valid),
+                    validate: Arc::new(|_| ValidationResult::Valid),
                     value_from_form_value: Arc::new(|value| {
                         serde_json::Value::Bool(value.is_some())
                     }),
@@ -291,31 +964,102 @@ impl FieldConfig {
         }
     }
 
-    pub fn create_number_config(field_id: String, display_name: String, read_only: bool) -> Self {
+    /// A boolean field shown as a toggle switch rather than a checkbox
+    pub fn create_toggle_config(field_id: String, display_name: String, read_only: bool) -> Self {
+        let boolean_config =
+            Self::create_boolean_config(field_id.clone(), display_name.clone(), read_only);
+
+        Self {
+            render: Arc::new(move |value, constraints| {
+                Ok(render_toggle_widget(
+                    field_id.clone(),
+                    display_name.clone(),
+                    value,
+                    constraints.html_attributes(),
+                ))
+            }),
+            ..boolean_config
+        }
+    }
+
+    /// A number field parsed as described by the number config. Its `step`, `min` and `max`
+    /// attributes are added to the input unless the field's constraints set them.
+    pub fn create_number_config(
+        field_id: String,
+        display_name: String,
+        read_only: bool,
+        number_config: NumberConfig,
+    ) -> Self {
+        let number_attributes = number_config.html_attributes();
+        let number_config = Arc::new(number_config);
+
         Self {
             field_id: field_id.clone(),
             display_name: display_name.clone(),
             help_text: None,
             description: None,
-            render: Arc::new(move |value| {
+            render: Arc::new(move |value, constraints| {
+                let mut attributes = constraints.html_attributes();
+                for (name, value) in &number_attributes {
+                    if !attributes.iter().any(|(existing, _)| existing == name) {
+                        attributes.push((name.clone(), value.clone()));
+                    }
+                }
+
                 Ok(render_number_input_widget(
                     field_id.clone(),
                     display_name.clone(),
                     value,
+                    attributes,
                 ))
             }),
+            display: None,
+            sortable: false,
+            searchable: false,
+            skip_create: false,
+            nullable: false,
+            relation: None,
+            many: false,
+            constraints: FieldConstraints::default(),
+            filter_config: Some(FilterConfig::NumberRange),
             create_config: if read_only {
                 None
             } else {
+                let validate_config = number_config.clone();
+
                 Some(CreateConfig {
-                    validate: Arc::new(|value| ValidationResult::Valid),
-                    value_from_form_value: Arc::new(|value| {
-                        serde_json::Value::Number(value.unwrap().parse().unwrap())
+                    validate: Arc::new(move |value| validate_config.validate(&value)),
+                    value_from_form_value: Arc::new(move |value| {
+                        number_config.value_from_form_value(value)
                     }),
                 })
             },
         }
     }
+
+    /// A whole number field, taking any value which fits in an `i64`
+    pub fn create_integer_config(field_id: String, display_name: String, read_only: bool) -> Self {
+        Self::create_number_config(
+            field_id,
+            display_name,
+            read_only,
+            NumberConfig::integer(i64::MIN.into(), i64::MAX.into()),
+        )
+    }
+
+    /// A floating point field, taking any finite `f64`
+    pub fn create_float_config(field_id: String, display_name: String, read_only: bool) -> Self {
+        Self::create_number_config(
+            field_id,
+            display_name,
+            read_only,
+            NumberConfig::float(f64::MIN, f64::MAX),
+        )
+    }
+
+    pub fn create_decimal_config(field_id: String, display_name: String, read_only: bool) -> Self {
+        Self::create_number_config(field_id, display_name, read_only, NumberConfig::decimal())
+    }
 }
 
 pub fn add(left: u64, right: u64) -> u64 {
@@ -331,4 +1075,126 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Input {
+        name: String,
+        age: u8,
+    }
+
+    #[test]
+    fn input_from_json_reports_failing_field() {
+        let result = input_from_json::<Input>(serde_json::json!({ "name": "Susan", "age": 300 }));
+        match result {
+            Err(AdminError::Validation(errors)) => {
+                assert!(errors.field_error("age").is_some());
+                assert!(errors.form_errors.is_empty());
+            }
+            other => panic!("Expected a validation error, got {:?}", other),
+        }
+
+        let result = input_from_json::<Input>(serde_json::json!({ "name": "Susan" }));
+        match result {
+            Err(AdminError::Validation(errors)) => {
+                assert!(errors.field_errors.is_empty());
+                assert_eq!(errors.form_errors.len(), 1);
+            }
+            other => panic!("Expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nullable_fields_are_null_when_marked_empty() {
+        let field_configs =
+            vec![
+                FieldConfig::create_integer_config("age".to_string(), "Age".to_string(), false)
+                    .nullable(),
+            ];
+
+        let json = json_from_form_data(&field_configs, b"age=&age__empty=true");
+        assert_eq!(json.get("age"), Some(&serde_json::Value::Null));
+        assert_eq!(
+            field_configs[0].validation_error(serde_json::Value::Null),
+            None
+        );
+
+        let json = json_from_form_data(&field_configs, b"age=42");
+        assert_eq!(json.get("age"), Some(&serde_json::json!(42)));
+
+        let required = field_configs[0].clone().with_constraints(FieldConstraints {
+            required: true,
+            ..FieldConstraints::default()
+        });
+        assert!(required.validation_error(serde_json::Value::Null).is_some());
+    }
+
+    #[test]
+    fn number_fields_take_every_value_of_their_type() {
+        let field_configs = vec![u128::field_config(
+            "total".to_string(),
+            "Total".to_string(),
+            false,
+        )];
+        let json = json_from_form_data(&field_configs, b"total=18446744073709551615");
+        assert_eq!(
+            field_configs[0].validation_error(json["total"].clone()),
+            None
+        );
+        assert_eq!(
+            serde_json::from_value::<u128>(json["total"].clone()).unwrap(),
+            u64::MAX.into()
+        );
+        // Json numbers can't hold any more
+        let json = json_from_form_data(&field_configs, b"total=18446744073709551616");
+        assert!(field_configs[0]
+            .validation_error(json["total"].clone())
+            .is_some());
+
+        let field_configs = vec![i128::field_config(
+            "total".to_string(),
+            "Total".to_string(),
+            false,
+        )];
+        let json = json_from_form_data(&field_configs, b"total=-9223372036854775808");
+        assert_eq!(
+            field_configs[0].validation_error(json["total"].clone()),
+            None
+        );
+
+        // Empty values are only accepted by nullable fields
+        let field_config = f64::field_config("height".to_string(), "Height".to_string(), false);
+        assert_eq!(
+            field_config.validation_error(serde_json::Value::Null),
+            Some("This field is required".to_string())
+        );
+        let field_config =
+            Option::<f64>::field_config("height".to_string(), "Height".to_string(), false);
+        assert_eq!(field_config.validation_error(serde_json::Value::Null), None);
+    }
+
+    #[test]
+    fn list_fields_read_each_value_as_the_item_type() {
+        let field_configs =
+            vec![
+                FieldConfig::create_integer_config("ids".to_string(), "Ids".to_string(), false)
+                    .many(),
+            ];
+
+        let json = json_from_form_data(&field_configs, b"ids=%5B%221%22%2C%222%22%5D");
+        assert_eq!(json.get("ids"), Some(&serde_json::json!([1, 2])));
+        let json = json_from_form_data(&field_configs, b"ids=");
+        assert_eq!(json.get("ids"), Some(&serde_json::json!([])));
+
+        assert_eq!(
+            field_configs[0].validation_error(serde_json::json!([1, 2])),
+            None
+        );
+        assert!(field_configs[0]
+            .validation_error(serde_json::json!(["x"]))
+            .is_some());
+        assert!(field_configs[0]
+            .validation_error(serde_json::json!("x"))
+            .is_some());
+    }
 }
@@ -0,0 +1,588 @@
+use crate::{id_from_json, FieldConfig};
+
+pub const DEFAULT_PAGE_SIZE: u64 = 25;
+pub const MAX_PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "asc",
+            SortDirection::Descending => "desc",
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListSort {
+    pub field_id: String,
+    pub direction: SortDirection,
+}
+
+/// The kind of filter the list view offers for a field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterConfig {
+    /// Filter on the field being true or false
+    Boolean,
+    /// Filter on the field being within an (inclusive) range
+    NumberRange,
+    /// Filter on the field containing or starting with some text
+    Text,
+    /// Filter on the field holding the id of a particular item, for relation fields
+    Relation,
+}
+
+impl FilterConfig {
+    pub fn supports(&self, condition: &FilterCondition) -> bool {
+        matches!(
+            (self, condition),
+            (FilterConfig::Boolean, FilterCondition::Equals(_))
+                | (FilterConfig::NumberRange, FilterCondition::Range { .. })
+                | (FilterConfig::Text, FilterCondition::Contains(_))
+                | (FilterConfig::Text, FilterCondition::StartsWith(_))
+                | (FilterConfig::Relation, FilterCondition::Id(_))
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterCondition {
+    Equals(bool),
+    Range {
+        min: Option<serde_json::Number>,
+        max: Option<serde_json::Number>,
+    },
+    Contains(String),
+    StartsWith(String),
+    // The id of the related item, as used in urls
+    Id(String),
+}
+
+impl FilterCondition {
+    /// Checks a json value against the condition. This is intended for data sources which filter
+    /// in memory, text matching is case insensitive and numbers are compared as floats.
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        match self {
+            FilterCondition::Equals(expected) => value.as_bool() == Some(*expected),
+            FilterCondition::Range { min, max } => value.as_f64().is_some_and(|value| {
+                min.as_ref()
+                    .and_then(|min| min.as_f64())
+                    .is_none_or(|min| value >= min)
+                    && max
+                        .as_ref()
+                        .and_then(|max| max.as_f64())
+                        .is_none_or(|max| value <= max)
+            }),
+            FilterCondition::Contains(text) => value
+                .as_str()
+                .is_some_and(|value| value.to_lowercase().contains(&text.to_lowercase())),
+            FilterCondition::StartsWith(text) => value
+                .as_str()
+                .is_some_and(|value| value.to_lowercase().starts_with(&text.to_lowercase())),
+            // Fields relating to many items match if any of them is the item
+            FilterCondition::Id(id) => match value {
+                serde_json::Value::Array(values) => values
+                    .iter()
+                    .any(|value| id_from_json(value).as_ref() == Some(id)),
+                value => id_from_json(value).as_ref() == Some(id),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListFilter {
+    pub field_id: String,
+    pub condition: FilterCondition,
+}
+
+impl ListFilter {
+    /// Checks the filter against an item serialized to json, see [`FilterCondition::matches`]
+    pub fn matches(&self, item: &serde_json::Value) -> bool {
+        item.get(&self.field_id)
+            .is_some_and(|value| self.condition.matches(value))
+    }
+}
+
+/// A free text search across the fields configured as searchable
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListSearch {
+    pub term: String,
+    /// The fields the term should be searched for in
+    pub field_ids: Vec<String>,
+}
+
+impl ListSearch {
+    /// Checks if any of the searched fields of an item serialized to json contain the term. As with
+    /// [`FilterCondition::matches`] this is intended for data sources which search in memory.
+    pub fn matches(&self, item: &serde_json::Value) -> bool {
+        let term = self.term.to_lowercase();
+
+        self.field_ids
+            .iter()
+            .any(|field_id| match item.get(field_id) {
+                Some(serde_json::Value::String(value)) => value.to_lowercase().contains(&term),
+                Some(serde_json::Value::Null) | None => false,
+                Some(value) => value.to_string().to_lowercase().contains(&term),
+            })
+    }
+}
+
+/// Describes which page of items should be returned by a resource's list callback
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListQuery {
+    /// The page to return, starting at 1
+    pub page: u64,
+    pub page_size: u64,
+    /// Opaque cursor returned as `next_cursor` by the previous page. Data sources which support
+    /// cursor based pagination should prefer this over `page` when it is set
+    pub cursor: Option<String>,
+    /// The field to order items by. Only fields configured as sortable are ever passed through
+    pub sort: Option<ListSort>,
+    /// Filters which every returned item must match. Only filters supported by the field's
+    /// `filter_config` are ever passed through
+    pub filters: Vec<ListFilter>,
+    /// Free text search term, along with the searchable fields it applies to
+    pub search: Option<ListSearch>,
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            page_size: DEFAULT_PAGE_SIZE,
+            cursor: None,
+            sort: None,
+            filters: vec![],
+            search: None,
+        }
+    }
+}
+
+impl ListQuery {
+    /// Parses a list query from a url query string, e.g.
+    /// `page=2&page_size=50&sort=name&order=desc`. The search term is passed as `q`, the fields it
+    /// applies to are only known once [`ListQuery::retain_supported`] is called. Filters are
+    /// encoded as `<field_id>__<op>=<value>` where op is one of `eq`, `gte`, `lte`, `contains`,
+    /// `starts_with` or `id`. Missing or invalid values fall back to their defaults.
+    pub fn from_query_string(query: &str) -> Self {
+        let mut direction = SortDirection::Ascending;
+
+        let mut list_query = form_urlencoded::parse(query.as_bytes()).fold(
+            ListQuery::default(),
+            |mut list_query, (key, value)| {
+                match key.as_ref() {
+                    "page" => {
+                        if let Ok(page) = value.parse::<u64>() {
+                            list_query.page = page.max(1);
+                        }
+                    }
+                    "page_size" => {
+                        if let Ok(page_size) = value.parse::<u64>() {
+                            list_query.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+                        }
+                    }
+                    "cursor" if !value.is_empty() => {
+                        list_query.cursor = Some(value.into_owned());
+                    }
+                    "sort" if !value.is_empty() => {
+                        list_query.sort = Some(ListSort {
+                            field_id: value.into_owned(),
+                            direction: SortDirection::Ascending,
+                        });
+                    }
+                    "order" if value == "desc" => direction = SortDirection::Descending,
+                    "q" if !value.trim().is_empty() => {
+                        list_query.search = Some(ListSearch {
+                            term: value.trim().to_string(),
+                            field_ids: vec![],
+                        });
+                    }
+                    key if !value.is_empty() => {
+                        if let Some((field_id, op)) = key.rsplit_once("__") {
+                            list_query.add_filter(field_id, op, &value);
+                        }
+                    }
+                    _ => {}
+                }
+                list_query
+            },
+        );
+
+        if let Some(sort) = &mut list_query.sort {
+            sort.direction = direction;
+        }
+
+        list_query
+    }
+
+    fn add_filter(&mut self, field_id: &str, op: &str, value: &str) {
+        let condition = match op {
+            "eq" => match value {
+                "true" => FilterCondition::Equals(true),
+                "false" => FilterCondition::Equals(false),
+                _ => return,
+            },
+            "gte" | "lte" => {
+                let Ok(bound) = value.parse::<serde_json::Number>() else {
+                    return;
+                };
+
+                // Both ends of a range are kept in a single filter
+                let existing_range = self.filters.iter_mut().find_map(|filter| {
+                    match (filter.field_id == field_id, &mut filter.condition) {
+                        (true, FilterCondition::Range { min, max }) => Some((min, max)),
+                        _ => None,
+                    }
+                });
+
+                match (existing_range, op) {
+                    (Some((min, _)), "gte") => *min = Some(bound),
+                    (Some((_, max)), _) => *max = Some(bound),
+                    (None, "gte") => {
+                        self.filters.push(ListFilter {
+                            field_id: field_id.to_string(),
+                            condition: FilterCondition::Range {
+                                min: Some(bound),
+                                max: None,
+                            },
+                        });
+                    }
+                    (None, _) => {
+                        self.filters.push(ListFilter {
+                            field_id: field_id.to_string(),
+                            condition: FilterCondition::Range {
+                                min: None,
+                                max: Some(bound),
+                            },
+                        });
+                    }
+                }
+                return;
+            }
+            "contains" => FilterCondition::Contains(value.to_string()),
+            "starts_with" => FilterCondition::StartsWith(value.to_string()),
+            "id" => FilterCondition::Id(value.to_string()),
+            _ => return,
+        };
+
+        self.filters.push(ListFilter {
+            field_id: field_id.to_string(),
+            condition,
+        });
+    }
+
+    /// The key value pairs this query is encoded as in a url, see [`ListQuery::from_query_string`]
+    pub fn to_query_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = vec![("page".to_string(), self.page.to_string())];
+        if self.page_size != DEFAULT_PAGE_SIZE {
+            pairs.push(("page_size".to_string(), self.page_size.to_string()));
+        }
+        if let Some(cursor) = &self.cursor {
+            pairs.push(("cursor".to_string(), cursor.clone()));
+        }
+        if let Some(sort) = &self.sort {
+            pairs.push(("sort".to_string(), sort.field_id.clone()));
+            pairs.push(("order".to_string(), sort.direction.as_str().to_string()));
+        }
+        if let Some(search) = &self.search {
+            pairs.push(("q".to_string(), search.term.clone()));
+        }
+        for filter in &self.filters {
+            let field_id = &filter.field_id;
+            match &filter.condition {
+                FilterCondition::Equals(value) => {
+                    pairs.push((format!("{field_id}__eq"), value.to_string()));
+                }
+                FilterCondition::Range { min, max } => {
+                    if let Some(min) = min {
+                        pairs.push((format!("{field_id}__gte"), min.to_string()));
+                    }
+                    if let Some(max) = max {
+                        pairs.push((format!("{field_id}__lte"), max.to_string()));
+                    }
+                }
+                FilterCondition::Contains(value) => {
+                    pairs.push((format!("{field_id}__contains"), value.clone()));
+                }
+                FilterCondition::StartsWith(value) => {
+                    pairs.push((format!("{field_id}__starts_with"), value.clone()));
+                }
+                FilterCondition::Id(id) => {
+                    pairs.push((format!("{field_id}__id"), id.clone()));
+                }
+            }
+        }
+        pairs
+    }
+
+    pub fn to_query_string(&self) -> String {
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.to_query_pairs())
+            .finish()
+    }
+
+    /// Drops any sort or filters the fields don't allow, so data sources are only ever asked for
+    /// what they have opted in to. The search is pointed at the searchable fields, or dropped if
+    /// there are none.
+    pub fn retain_supported(&mut self, field_configs: &[FieldConfig]) {
+        let field_config = |field_id: &str| {
+            field_configs
+                .iter()
+                .find(|config| config.field_id == field_id)
+        };
+
+        self.sort = self
+            .sort
+            .take()
+            .filter(|sort| field_config(&sort.field_id).is_some_and(|config| config.sortable));
+        self.filters.retain(|filter| {
+            field_config(&filter.field_id)
+                .and_then(|config| config.filter_config)
+                .is_some_and(|filter_config| filter_config.supports(&filter.condition))
+        });
+
+        let searchable_field_ids: Vec<String> = field_configs
+            .iter()
+            .filter(|config| config.searchable)
+            .map(|config| config.field_id.clone())
+            .collect();
+        self.search = self
+            .search
+            .take()
+            .filter(|_| !searchable_field_ids.is_empty())
+            .map(|search| ListSearch {
+                term: search.term,
+                field_ids: searchable_field_ids,
+            });
+    }
+
+    /// The query for the first page sorted by the given field. Sorting by the field the list is
+    /// already sorted by flips the direction.
+    pub fn sorted_by(&self, field_id: &str) -> ListQuery {
+        let direction = match &self.sort {
+            Some(sort) if sort.field_id == field_id => sort.direction.reverse(),
+            _ => SortDirection::Ascending,
+        };
+
+        ListQuery {
+            page: 1,
+            cursor: None,
+            sort: Some(ListSort {
+                field_id: field_id.to_string(),
+                direction,
+            }),
+            ..self.clone()
+        }
+    }
+
+    /// The condition currently applied to a field, if any
+    pub fn filter_for(&self, field_id: &str) -> Option<&FilterCondition> {
+        self.filters
+            .iter()
+            .find(|filter| filter.field_id == field_id)
+            .map(|filter| &filter.condition)
+    }
+
+    /// The number of items to skip to reach the start of this page, for offset based data sources.
+    /// Pages too far in to reach saturate rather than overflow.
+    pub fn offset(&self) -> u64 {
+        (self.page.max(1) - 1).saturating_mul(self.page_size)
+    }
+
+    /// The query for the page after this one, if the returned page indicates there is one
+    pub fn next_page<TItem>(&self, list_page: &ListPage<TItem>) -> Option<ListQuery> {
+        let has_next_page = match (&list_page.next_cursor, list_page.total) {
+            (Some(_), _) => true,
+            (None, Some(total)) => self.page.saturating_mul(self.page_size) < total,
+            (None, None) => list_page.items.len() as u64 >= self.page_size,
+        };
+
+        // There's no page after the last one a page number can hold
+        let page = self.page.checked_add(1)?;
+
+        has_next_page.then(|| ListQuery {
+            page,
+            cursor: list_page.next_cursor.clone(),
+            ..self.clone()
+        })
+    }
+
+    /// The query for the page before this one. Cursors can only move forwards so the previous page
+    /// is always requested by page number
+    pub fn previous_page(&self) -> Option<ListQuery> {
+        (self.page > 1).then(|| ListQuery {
+            page: self.page - 1,
+            cursor: None,
+            ..self.clone()
+        })
+    }
+}
+
+/// A single page of items returned by a resource's list callback
+pub struct ListPage<TItem> {
+    pub items: Vec<TItem>,
+    /// Total number of items across all pages, if the data source can cheaply provide it
+    pub total: Option<u64>,
+    /// Cursor to fetch the next page with, for data sources using cursor based pagination
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_query_round_trips_through_query_string() {
+        let query =
+            ListQuery::from_query_string("page=3&page_size=10&cursor=abc&sort=name&order=desc");
+        assert_eq!(
+            query,
+            ListQuery {
+                page: 3,
+                page_size: 10,
+                cursor: Some("abc".to_string()),
+                sort: Some(ListSort {
+                    field_id: "name".to_string(),
+                    direction: SortDirection::Descending,
+                }),
+                filters: vec![],
+                search: None,
+            }
+        );
+        assert_eq!(
+            ListQuery::from_query_string(&query.to_query_string()),
+            query
+        );
+    }
+
+    #[test]
+    fn list_query_ignores_invalid_values() {
+        let query = ListQuery::from_query_string("page=0&page_size=abc&other=1");
+        assert_eq!(query, ListQuery::default());
+    }
+
+    #[test]
+    fn list_query_next_page_uses_total_when_known() {
+        let query = ListQuery {
+            page: 2,
+            page_size: 10,
+            ..ListQuery::default()
+        };
+        let page = |total| ListPage::<()> {
+            items: vec![],
+            total: Some(total),
+            next_cursor: None,
+        };
+
+        assert_eq!(query.next_page(&page(20)), None);
+        assert_eq!(query.next_page(&page(21)).map(|query| query.page), Some(3));
+    }
+
+    #[test]
+    fn list_query_huge_pages_do_not_overflow() {
+        let query = ListQuery::from_query_string("page=18446744073709551615&page_size=50");
+        assert_eq!(query.page, u64::MAX);
+        assert_eq!(query.offset(), u64::MAX);
+
+        let list_page = ListPage::<()> {
+            items: vec![],
+            total: Some(u64::MAX),
+            next_cursor: Some("next".to_string()),
+        };
+        assert_eq!(query.next_page(&list_page), None);
+        assert_eq!(
+            query.previous_page().map(|query| query.page),
+            Some(u64::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn list_query_sorted_by_toggles_direction() {
+        let query = ListQuery::default().sorted_by("name");
+        assert_eq!(
+            query.sort.as_ref().map(|sort| sort.direction),
+            Some(SortDirection::Ascending)
+        );
+
+        let query = ListQuery { page: 4, ..query }.sorted_by("name");
+        assert_eq!(query.page, 1);
+        assert_eq!(
+            query.sort.as_ref().map(|sort| sort.direction),
+            Some(SortDirection::Descending)
+        );
+    }
+
+    #[test]
+    fn list_query_parses_filters() {
+        let query = ListQuery::from_query_string(
+            "is_adult__eq=true&age__gte=18&age__lte=65.5&name__contains=su&\
+             name__starts_with=&x__y=1&team_id__id=3",
+        );
+        assert_eq!(
+            query.filters,
+            vec![
+                ListFilter {
+                    field_id: "is_adult".to_string(),
+                    condition: FilterCondition::Equals(true),
+                },
+                ListFilter {
+                    field_id: "age".to_string(),
+                    condition: FilterCondition::Range {
+                        min: Some(18.into()),
+                        max: serde_json::Number::from_f64(65.5),
+                    },
+                },
+                ListFilter {
+                    field_id: "name".to_string(),
+                    condition: FilterCondition::Contains("su".to_string()),
+                },
+                ListFilter {
+                    field_id: "team_id".to_string(),
+                    condition: FilterCondition::Id("3".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            ListQuery::from_query_string(&query.to_query_string()),
+            query
+        );
+    }
+
+    #[test]
+    fn filter_condition_matches_values() {
+        let range = FilterCondition::Range {
+            min: Some(1.into()),
+            max: Some(10.into()),
+        };
+        assert!(range.matches(&serde_json::json!(10)));
+        assert!(!range.matches(&serde_json::json!(11)));
+        assert!(FilterCondition::StartsWith("su".to_string()).matches(&serde_json::json!("Susan")));
+        assert!(!FilterCondition::Contains("x".to_string()).matches(&serde_json::json!("Susan")));
+        assert!(FilterCondition::Equals(false).matches(&serde_json::json!(false)));
+        assert!(FilterCondition::Id("3".to_string()).matches(&serde_json::json!(3)));
+        assert!(FilterCondition::Id("a".to_string()).matches(&serde_json::json!("a")));
+    }
+
+    #[test]
+    fn list_search_matches_any_searched_field() {
+        let search = ListSearch {
+            term: "SUS".to_string(),
+            field_ids: vec!["id".to_string(), "name".to_string()],
+        };
+        assert!(search.matches(&serde_json::json!({ "id": 1, "name": "Susan" })));
+        assert!(!search.matches(&serde_json::json!({ "id": 1, "name": "Bob", "other": "sus" })));
+    }
+}
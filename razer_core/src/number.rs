@@ -0,0 +1,257 @@
+use crate::ValidationResult;
+
+/// The kind of number a number field holds, which decides how its form value is parsed
+#[derive(Clone, Debug, PartialEq)]
+pub enum NumberKind {
+    /// Whole numbers between `min` and `max`, e.g. the range of the field's integer type
+    Integer { min: i128, max: i128 },
+    /// Floating point numbers between `min` and `max`, e.g. the range of the field's float type
+    Float { min: f64, max: f64 },
+    /// Exact decimals. They're kept as strings in the json so no precision is lost
+    Decimal,
+}
+
+/// How a number field's values are entered and checked
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumberConfig {
+    pub kind: NumberKind,
+    // The input's `step` attribute, e.g. "0.01" for amounts of money. When not given integers
+    // step by 1, and other numbers by the precision if there is one
+    pub step: Option<String>,
+    // The most decimal places a value can have
+    pub precision: Option<u32>,
+}
+
+impl NumberConfig {
+    pub fn integer(min: i128, max: i128) -> Self {
+        Self {
+            kind: NumberKind::Integer { min, max },
+            step: None,
+            precision: None,
+        }
+    }
+
+    pub fn float(min: f64, max: f64) -> Self {
+        Self {
+            kind: NumberKind::Float { min, max },
+            step: None,
+            precision: None,
+        }
+    }
+
+    pub fn decimal() -> Self {
+        Self {
+            kind: NumberKind::Decimal,
+            step: None,
+            precision: None,
+        }
+    }
+
+    pub fn with_step(self, step: impl Into<String>) -> Self {
+        Self {
+            step: Some(step.into()),
+            ..self
+        }
+    }
+
+    pub fn with_precision(self, precision: u32) -> Self {
+        Self {
+            precision: Some(precision),
+            ..self
+        }
+    }
+
+    /// The `step`, `min` and `max` attributes for the number input
+    pub fn html_attributes(&self) -> Vec<(String, String)> {
+        let step = match (&self.step, &self.kind, self.precision) {
+            (Some(step), _, _) => step.clone(),
+            (None, NumberKind::Integer { .. }, _) => "1".to_string(),
+            (None, _, Some(0)) => "1".to_string(),
+            (None, _, Some(precision)) => format!("0.{}1", "0".repeat(precision as usize - 1)),
+            (None, _, None) => "any".to_string(),
+        };
+
+        let mut attributes = vec![("step".to_string(), step)];
+        if let NumberKind::Integer { min, max } = self.kind {
+            attributes.push(("min".to_string(), min.to_string()));
+            attributes.push(("max".to_string(), max.to_string()));
+        }
+        attributes
+    }
+
+    /// Reads a submitted form value. Empty values are null, and values which aren't a valid
+    /// number are kept as entered for the validator to report.
+    pub fn value_from_form_value(&self, value: Option<&String>) -> serde_json::Value {
+        let Some(value) = value
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+        else {
+            return serde_json::Value::Null;
+        };
+
+        let number = match self.kind {
+            NumberKind::Integer { .. } => value.parse::<i128>().ok().and_then(|number| {
+                i64::try_from(number)
+                    .map(serde_json::Number::from)
+                    .or_else(|_| u64::try_from(number).map(serde_json::Number::from))
+                    .ok()
+            }),
+            NumberKind::Float { .. } => value
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64),
+            NumberKind::Decimal => {
+                return serde_json::Value::String(value.to_string());
+            }
+        };
+
+        number
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|| serde_json::Value::String(value.to_string()))
+    }
+
+    /// Checks a value read by `value_from_form_value` is a number of the right kind. Empty values
+    /// are rejected, nullable fields accept them before this is called.
+    pub fn validate(&self, value: &serde_json::Value) -> ValidationResult {
+        let text = match value {
+            serde_json::Value::Null => {
+                return ValidationResult::Invalid("This field is required".to_string());
+            }
+            serde_json::Value::Number(number) => number.to_string(),
+            serde_json::Value::String(text) => text.clone(),
+            _ => return ValidationResult::Invalid("Enter a number".to_string()),
+        };
+
+        match self.kind {
+            NumberKind::Integer { min, max } => match text.parse::<i128>() {
+                Ok(number) if number < min => {
+                    return ValidationResult::Invalid(format!("Must be at least {}", min));
+                }
+                Ok(number) if number > max => {
+                    return ValidationResult::Invalid(format!("Must be at most {}", max));
+                }
+                Ok(_) => {}
+                Err(_) => return ValidationResult::Invalid("Enter a whole number".to_string()),
+            },
+            NumberKind::Float { min, max } => match value.as_f64() {
+                Some(number) if number < min => {
+                    return ValidationResult::Invalid(format!("Must be at least {}", min));
+                }
+                Some(number) if number > max => {
+                    return ValidationResult::Invalid(format!("Must be at most {}", max));
+                }
+                Some(_) => {}
+                None => return ValidationResult::Invalid("Enter a number".to_string()),
+            },
+            NumberKind::Decimal if !is_decimal(&text) => {
+                return ValidationResult::Invalid("Enter a number".to_string());
+            }
+            NumberKind::Decimal => {}
+        }
+
+        match self.precision {
+            Some(precision) if decimal_places(&text) > precision as usize => {
+                ValidationResult::Invalid(format!("Must have at most {} decimal places", precision))
+            }
+            _ => ValidationResult::Valid,
+        }
+    }
+}
+
+/// Whether the text is a plain decimal number, e.g. `-12.50`
+fn is_decimal(text: &str) -> bool {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    !whole.is_empty()
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit())
+}
+
+fn decimal_places(text: &str) -> usize {
+    let mantissa = text.split(['e', 'E']).next().unwrap_or(text);
+    mantissa
+        .split_once('.')
+        .map(|(_, fraction)| fraction.len())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_config_parses_and_validates_values() {
+        let integer = NumberConfig::integer(u8::MIN.into(), u8::MAX.into());
+        let value = integer.value_from_form_value(Some(&"300".to_string()));
+        assert_eq!(value, serde_json::json!(300));
+        assert!(matches!(
+            integer.validate(&value),
+            ValidationResult::Invalid(_)
+        ));
+        let value = integer.value_from_form_value(Some(&"1.5".to_string()));
+        assert!(matches!(
+            integer.validate(&value),
+            ValidationResult::Invalid(_)
+        ));
+
+        let big = NumberConfig::integer(u64::MIN.into(), u64::MAX.into());
+        let value = big.value_from_form_value(Some(&u64::MAX.to_string()));
+        assert_eq!(value, serde_json::json!(u64::MAX));
+        assert_eq!(big.validate(&value), ValidationResult::Valid);
+
+        let float = NumberConfig::float(f64::MIN, f64::MAX).with_precision(2);
+        let value = float.value_from_form_value(Some(&" 2.75 ".to_string()));
+        assert_eq!(value, serde_json::json!(2.75));
+        assert_eq!(float.validate(&value), ValidationResult::Valid);
+        let value = float.value_from_form_value(Some(&"2.755".to_string()));
+        assert!(matches!(
+            float.validate(&value),
+            ValidationResult::Invalid(_)
+        ));
+
+        let decimal = NumberConfig::decimal().with_precision(2);
+        let value = decimal.value_from_form_value(Some(&"10.50".to_string()));
+        assert_eq!(value, serde_json::json!("10.50"));
+        assert_eq!(decimal.validate(&value), ValidationResult::Valid);
+        let value = decimal.value_from_form_value(Some(&"ten".to_string()));
+        assert!(matches!(
+            decimal.validate(&value),
+            ValidationResult::Invalid(_)
+        ));
+
+        assert_eq!(
+            decimal.value_from_form_value(Some(&"".to_string())),
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn number_config_rejects_empty_and_out_of_range_values() {
+        let integer = NumberConfig::integer(i64::MIN.into(), i64::MAX.into());
+        let value = integer.value_from_form_value(Some(&"".to_string()));
+        assert_eq!(
+            integer.validate(&value),
+            ValidationResult::Invalid("This field is required".to_string())
+        );
+
+        let float = NumberConfig::float(f32::MIN.into(), f32::MAX.into());
+        let value = float.value_from_form_value(Some(&"1e39".to_string()));
+        assert!(matches!(
+            float.validate(&value),
+            ValidationResult::Invalid(_)
+        ));
+        let value = float.value_from_form_value(Some(&"-1e39".to_string()));
+        assert!(matches!(
+            float.validate(&value),
+            ValidationResult::Invalid(_)
+        ));
+        let value = float.value_from_form_value(Some(&"3.5e38".to_string()));
+        assert!(matches!(
+            float.validate(&value),
+            ValidationResult::Invalid(_)
+        ));
+        let value = float.value_from_form_value(Some(&"1e38".to_string()));
+        assert_eq!(float.validate(&value), ValidationResult::Valid);
+    }
+}
@@ -0,0 +1,310 @@
+use std::sync::Arc;
+
+use razer_ui::{empty_field_name, render_chips_widget, render_relation_widget};
+
+use crate::{
+    CreateConfig, FieldConfig, FilterCondition, FilterConfig, JsonResourceItem, ListFilter,
+    ListQuery, ValidationResult,
+};
+
+/// Where a relation field's value points, i.e. the resource it holds the id of
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelationConfig {
+    // The path of the related resource, which must be registered on the same `Admin`
+    pub resource_path: String,
+}
+
+impl RelationConfig {
+    /// The url the related resource is searched at by the relation widget
+    pub fn search_url(&self) -> String {
+        format!("/admin/{}/autocomplete", self.resource_path)
+    }
+
+    /// The url of the related item's detail page
+    pub fn item_url(&self, id: &str) -> String {
+        format!("/admin/{}/{}", self.resource_path, id)
+    }
+
+    /// The url the related item's label is loaded from
+    pub fn label_url(&self, id: &str) -> String {
+        format!("/admin/{}/{}/label", self.resource_path, id)
+    }
+}
+
+/// The items of another resource which relate to an item, shown in a list on the item's detail
+/// page, e.g. a customer's orders. The other resource must have a relation field pointing back at
+/// this one, which the list is filtered by and which new related items are created with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelatedList {
+    // The path of the related resource, which must be registered on the same `Admin`
+    pub resource_path: String,
+    // The related resource's relation field holding this item's id
+    pub field_id: String,
+}
+
+impl RelatedList {
+    pub fn new(resource_path: impl Into<String>, field_id: impl Into<String>) -> Self {
+        Self {
+            resource_path: resource_path.into(),
+            field_id: field_id.into(),
+        }
+    }
+
+    /// The query for the related items of the item with the given id
+    pub fn list_query(&self, id: &str) -> ListQuery {
+        ListQuery {
+            filters: vec![ListFilter {
+                field_id: self.field_id.clone(),
+                condition: FilterCondition::Id(id.to_string()),
+            }],
+            ..ListQuery::default()
+        }
+    }
+
+    /// The url the list is loaded from on the detail page of the item with the given id, served
+    /// under `resource_path`
+    pub fn section_url(&self, resource_path: &str, id: &str) -> String {
+        format!(
+            "/admin/{}/{}/related/{}/{}",
+            resource_path, id, self.resource_path, self.field_id
+        )
+    }
+
+    /// The related resource's list view, showing only the related items
+    pub fn list_url(&self, id: &str) -> String {
+        format!(
+            "/admin/{}?{}",
+            self.resource_path,
+            self.list_query(id).to_query_string()
+        )
+    }
+
+    /// The related resource's create view, with the relation field set to the given item
+    pub fn create_url(&self, id: &str) -> String {
+        format!(
+            "/admin/{}/create?{}",
+            self.resource_path,
+            form_urlencoded::Serializer::new(String::new())
+                .append_pair(&self.field_id, id)
+                .finish()
+        )
+    }
+}
+
+/// The resource a nested resource is served under, see [`crate::Admin::register_nested`]. Each
+/// item belongs to the parent item its relation field points at, e.g. a customer's addresses are
+/// served under `/admin/customers/{id}/addresses`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParentConfig {
+    // The path of the parent resource, which must be registered on the same `Admin`
+    pub resource_path: String,
+    // The nested resource's relation field holding the parent item's id
+    pub field_id: String,
+}
+
+impl ParentConfig {
+    /// The url the nested resource, served under `resource_path`, is listed at for the parent item
+    /// with the given id
+    pub fn list_url(&self, resource_path: &str, parent_id: &str) -> String {
+        format!(
+            "/admin/{}/{}/{}",
+            self.resource_path, parent_id, resource_path
+        )
+    }
+
+    /// The filter for the items of the parent item with the given id
+    pub fn list_filter(&self, parent_id: &str) -> ListFilter {
+        ListFilter {
+            field_id: self.field_id.clone(),
+            condition: FilterCondition::Id(parent_id.to_string()),
+        }
+    }
+
+    /// Whether an item belongs to the parent item with the given id
+    pub fn is_parent_of(&self, item: &JsonResourceItem, parent_id: &str) -> bool {
+        item.get(&self.field_id)
+            .and_then(id_from_json)
+            .is_some_and(|id| id == parent_id)
+    }
+
+    /// Submitted form data with the parent field set to the given parent item, whatever was
+    /// submitted for it
+    pub fn form_data(&self, data: &[u8], parent_id: &str) -> Vec<u8> {
+        let empty_field_id = empty_field_name(&self.field_id);
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+        for (key, value) in form_urlencoded::parse(data) {
+            if key != self.field_id && key != empty_field_id {
+                serializer.append_pair(&key, &value);
+            }
+        }
+
+        serializer
+            .append_pair(&self.field_id, parent_id)
+            .finish()
+            .into_bytes()
+    }
+}
+
+/// The values of a list field as the strings they're submitted as, along with the url each
+/// value's label is loaded from for relation fields
+pub(crate) fn chip_values(
+    value: &serde_json::Value,
+    relation: Option<&RelationConfig>,
+) -> Vec<(String, Option<String>)> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(id_from_json)
+        .map(|id| {
+            let label_url = relation.map(|relation| relation.label_url(&id));
+            (id, label_url)
+        })
+        .collect()
+}
+
+/// Converts an id in the json, e.g. a number or a string, into the string used in urls
+pub fn id_from_json(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(id) => Some(id.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+impl FieldConfig {
+    /// Makes the field a relation to the resource served under `resource_path`, e.g. an order's
+    /// `customer_id` pointing at `"customers"`. It's picked by searching the related resource, and
+    /// shown as a link to the related item labelled by the item's `label_field_id`. Values are
+    /// still read and validated as the field's own type, and must be picked unless the field is
+    /// nullable.
+    ///
+    /// Fields holding a list of ids, e.g. `Vec<i32>`, relate to many items which are picked as
+    /// chips. The data source reads and writes the linked ids along with the rest of the item,
+    /// e.g. loading them from a join table in `get` and `list` and replacing them in `create` and
+    /// `update`.
+    pub fn with_relation(self, resource_path: impl Into<String>) -> Self {
+        let relation = RelationConfig {
+            resource_path: resource_path.into(),
+        };
+        let field_id = self.field_id.clone();
+        let display_name = self.display_name.clone();
+        let render_relation = relation.clone();
+        let many = self.many;
+
+        Self {
+            render: Arc::new(move |value, constraints| {
+                if many {
+                    return Ok(render_chips_widget(
+                        field_id.clone(),
+                        display_name.clone(),
+                        chip_values(&value, Some(&render_relation)),
+                        Some(render_relation.search_url()),
+                        constraints.html_attributes(),
+                    ));
+                }
+
+                let id = id_from_json(&value);
+                Ok(render_relation_widget(
+                    field_id.clone(),
+                    display_name.clone(),
+                    id.clone(),
+                    render_relation.search_url(),
+                    id.map(|id| render_relation.label_url(&id)),
+                    constraints.html_attributes(),
+                ))
+            }),
+            filter_config: Some(FilterConfig::Relation),
+            relation: Some(relation),
+            create_config: match self.create_config {
+                Some(create_config) if !self.nullable && !self.many => {
+                    let validate = create_config.validate;
+
+                    Some(CreateConfig {
+                        validate: Arc::new(move |value| match value {
+                            serde_json::Value::Null => {
+                                ValidationResult::Invalid("Select an item".to_string())
+                            }
+                            value => validate(value),
+                        }),
+                        ..create_config
+                    })
+                }
+                create_config => create_config,
+            },
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relation_fields_read_values_as_the_field_type() {
+        let config = FieldConfig::create_integer_config(
+            "customer_id".to_string(),
+            "Customer".to_string(),
+            false,
+        )
+        .with_relation("customers");
+
+        let value =
+            (config.create_config.as_ref().unwrap().value_from_form_value)(Some(&"3".to_string()));
+        assert_eq!(value, serde_json::json!(3));
+        assert_eq!(config.validation_error(value.clone()), None);
+        assert!(config.validation_error(serde_json::Value::Null).is_some());
+
+        let html = config.render_widget(value).unwrap();
+        assert!(html.contains("/admin/customers/autocomplete"));
+        assert!(html.contains("/admin/customers/3/label"));
+    }
+
+    #[test]
+    fn related_lists_are_filtered_by_the_relation_field() {
+        let related_list = RelatedList::new("orders", "customer_id");
+
+        let list_query = ListQuery::from_query_string(
+            related_list
+                .list_url("7")
+                .split_once('?')
+                .map_or("", |(_, query)| query),
+        );
+        assert_eq!(list_query, related_list.list_query("7"));
+        assert!(list_query.filters[0].matches(&serde_json::json!({ "customer_id": 7 })));
+        assert_eq!(
+            related_list.create_url("7"),
+            "/admin/orders/create?customer_id=7"
+        );
+    }
+
+    #[test]
+    fn nested_items_belong_to_the_parent_in_their_parent_field() {
+        let parent = ParentConfig {
+            resource_path: "customers".to_string(),
+            field_id: "customer_id".to_string(),
+        };
+
+        assert_eq!(
+            parent.list_url("addresses", "7"),
+            "/admin/customers/7/addresses"
+        );
+        let item = serde_json::json!({ "id": 1, "customer_id": 7 });
+        assert!(parent.list_filter("7").matches(&item));
+        assert!(parent.is_parent_of(item.as_object().unwrap(), "7"));
+        assert!(!parent.is_parent_of(item.as_object().unwrap(), "8"));
+
+        // Whatever is submitted for the parent field is replaced
+        let data = parent.form_data(b"street=High+St&customer_id=8&customer_id__empty=true", "7");
+        let mut pairs: Vec<(String, String)> = form_urlencoded::parse(&data).into_owned().collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                ("customer_id".to_string(), "7".to_string()),
+                ("street".to_string(), "High St".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::{CreateConfig, FieldConfig, FieldConstraints, ValidationResult};
+use razer_ui::{render_radio_widget, render_select_widget};
+
+/// One of the values a select field can be set to
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectOption {
+    // The value in the json, e.g. the serialized enum variant
+    pub value: String,
+    pub label: String,
+}
+
+impl SelectOption {
+    pub fn new(value: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            label: label.into(),
+        }
+    }
+}
+
+/// A unit-only enum which is picked from a list of options. Derive it with
+/// `#[derive(AdminSelect)]`, which also implements `AdminField` so the enum can be used in models.
+pub trait AdminSelect: Serialize + Sized {
+    fn variants() -> Vec<Self>;
+
+    fn label(&self) -> String;
+}
+
+/// The options for an `AdminSelect` enum. Values are the serialized variants, so they follow any
+/// serde renames.
+pub fn select_options<T: AdminSelect>() -> Vec<SelectOption> {
+    T::variants()
+        .iter()
+        .map(|variant| {
+            let value = match serde_json::to_value(variant) {
+                Ok(serde_json::Value::String(value)) => value,
+                Ok(value) => value.to_string(),
+                Err(_) => variant.label(),
+            };
+            SelectOption::new(value, variant.label())
+        })
+        .collect()
+}
+
+fn option_widget_config(
+    field_id: String,
+    display_name: String,
+    read_only: bool,
+    options: Vec<SelectOption>,
+    render_widget: fn(
+        String,
+        String,
+        Option<String>,
+        Vec<(String, String)>,
+        Vec<(String, String)>,
+    ) -> String,
+) -> FieldConfig {
+    let options = Arc::new(options);
+    let render_options = options.clone();
+    let display_options = options.clone();
+
+    FieldConfig {
+        field_id: field_id.clone(),
+        display_name: display_name.clone(),
+        help_text: None,
+        description: None,
+        render: Arc::new(move |value, constraints: &FieldConstraints| {
+            Ok(render_widget(
+                field_id.clone(),
+                display_name.clone(),
+                value.as_str().map(|value| value.to_string()),
+                render_options
+                    .iter()
+                    .map(|option| (option.value.clone(), option.label.clone()))
+                    .collect(),
+                constraints.html_attributes(),
+            ))
+        }),
+        // Shown by label rather than value
+        display: Some(Arc::new(move |value| {
+            display_options
+                .iter()
+                .find(|option| value.as_str() == Some(&option.value))
+                .map(|option| option.label.clone())
+                .unwrap_or_else(|| value.to_string())
+        })),
+        sortable: false,
+        searchable: false,
+        skip_create: false,
+        nullable: false,
+        relation: None,
+        many: false,
+        constraints: FieldConstraints::default(),
+        filter_config: None,
+        create_config: if read_only {
+            None
+        } else {
+            Some(CreateConfig {
+                validate: Arc::new(move |value| match value.as_str() {
+                    Some(value) if options.iter().any(|option| option.value == value) => {
+                        ValidationResult::Valid
+                    }
+                    _ => ValidationResult::Invalid("Select one of the options".to_string()),
+                }),
+                value_from_form_value: Arc::new(|value| {
+                    value
+                        .filter(|value| !value.is_empty())
+                        .map(|value| serde_json::Value::String(value.clone()))
+                        .unwrap_or(serde_json::Value::Null)
+                }),
+            })
+        },
+    }
+}
+
+impl FieldConfig {
+    /// A field picked from a list of options with a select
+    pub fn create_select_config(
+        field_id: String,
+        display_name: String,
+        read_only: bool,
+        options: Vec<SelectOption>,
+    ) -> Self {
+        option_widget_config(
+            field_id,
+            display_name,
+            read_only,
+            options,
+            render_select_widget,
+        )
+    }
+
+    /// A field picked from a list of options with radio buttons
+    pub fn create_radio_config(
+        field_id: String,
+        display_name: String,
+        read_only: bool,
+        options: Vec<SelectOption>,
+    ) -> Self {
+        option_widget_config(
+            field_id,
+            display_name,
+            read_only,
+            options,
+            render_radio_widget,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Status {
+        Active,
+        OnHold,
+    }
+
+    impl AdminSelect for Status {
+        fn variants() -> Vec<Self> {
+            vec![Status::Active, Status::OnHold]
+        }
+
+        fn label(&self) -> String {
+            match self {
+                Status::Active => "Active".to_string(),
+                Status::OnHold => "On hold".to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn select_options_follow_serde_renames() {
+        assert_eq!(
+            select_options::<Status>(),
+            vec![
+                SelectOption::new("active", "Active"),
+                SelectOption::new("on_hold", "On hold"),
+            ]
+        );
+    }
+
+    #[test]
+    fn select_config_round_trips_option_values() {
+        let config = FieldConfig::create_select_config(
+            "status".to_string(),
+            "Status".to_string(),
+            false,
+            select_options::<Status>(),
+        );
+        let create_config = config.create_config.as_ref().unwrap();
+
+        let value = (create_config.value_from_form_value)(Some(&"on_hold".to_string()));
+        assert_eq!(value, serde_json::json!("on_hold"));
+        assert_eq!(
+            (create_config.validate)(value.clone()),
+            ValidationResult::Valid
+        );
+        assert_eq!((config.display.as_ref().unwrap())(&value), "On hold");
+
+        assert_eq!(
+            (create_config.value_from_form_value)(Some(&"".to_string())),
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn select_config_rejects_unknown_values() {
+        let config = FieldConfig::create_radio_config(
+            "status".to_string(),
+            "Status".to_string(),
+            false,
+            select_options::<Status>(),
+        );
+        let create_config = config.create_config.as_ref().unwrap();
+
+        let value = (create_config.value_from_form_value)(Some(&"archived".to_string()));
+        assert!(matches!(
+            (create_config.validate)(value),
+            ValidationResult::Invalid(_)
+        ));
+        assert!(matches!(
+            (create_config.validate)(serde_json::Value::Null),
+            ValidationResult::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use crate::{FieldConfig, JsonResourceItem};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationResult {
+    Invalid(String),
+    Valid,
+}
+
+/// Validation messages for a submitted form, either attached to a specific field or to the form
+/// as a whole
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ValidationErrors {
+    // Field id to message
+    pub field_errors: HashMap<String, String>,
+    pub form_errors: Vec<String>,
+}
+
+impl ValidationErrors {
+    pub fn is_empty(&self) -> bool {
+        self.field_errors.is_empty() && self.form_errors.is_empty()
+    }
+
+    pub fn add_field_error(&mut self, field_id: impl Into<String>, message: impl Into<String>) {
+        self.field_errors.insert(field_id.into(), message.into());
+    }
+
+    pub fn add_form_error(&mut self, message: impl Into<String>) {
+        self.form_errors.push(message.into());
+    }
+
+    pub fn field_error(&self, field_id: &str) -> Option<&String> {
+        self.field_errors.get(field_id)
+    }
+
+    /// `Ok` when there are no errors, for returning from the resource level validation hooks
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// Declarative constraints on a field's value. They are checked when the form is submitted and
+/// also rendered as the matching HTML constraint attributes on the field's widget.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FieldConstraints {
+    pub required: bool,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    // Lengths are counted in characters
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub pattern: Option<Pattern>,
+    pub email: bool,
+    pub url: bool,
+}
+
+/// A regex a value has to match in full, as with the HTML pattern attribute. It's compiled when
+/// it's built, so an invalid pattern is caught there rather than when a form is validated.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    pattern: String,
+    regex: regex::Regex,
+}
+
+impl Pattern {
+    pub fn new(pattern: impl Into<String>) -> Result<Self, regex::Error> {
+        let pattern = pattern.into();
+        let regex = regex::Regex::new(&format!("^(?:{})$", pattern))?;
+
+        Ok(Self { pattern, regex })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+fn is_empty_value(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(value) => value.trim().is_empty(),
+        // A required checkbox has to be checked
+        serde_json::Value::Bool(value) => !value,
+        // A required list needs at least one value
+        serde_json::Value::Array(values) => values.is_empty(),
+        _ => false,
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.split('.').count() > 1
+                && domain.split('.').all(|part| !part.is_empty())
+                && !value.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+impl FieldConstraints {
+    /// The HTML constraint attributes matching these constraints, as name and value pairs
+    pub fn html_attributes(&self) -> Vec<(String, String)> {
+        let mut attributes = vec![];
+
+        if self.required {
+            attributes.push(("required".to_string(), String::new()));
+        }
+        if let Some(min) = self.min {
+            attributes.push(("min".to_string(), min.to_string()));
+        }
+        if let Some(max) = self.max {
+            attributes.push(("max".to_string(), max.to_string()));
+        }
+        if let Some(min_length) = self.min_length {
+            attributes.push(("minlength".to_string(), min_length.to_string()));
+        }
+        if let Some(max_length) = self.max_length {
+            attributes.push(("maxlength".to_string(), max_length.to_string()));
+        }
+        if let Some(pattern) = &self.pattern {
+            attributes.push(("pattern".to_string(), pattern.as_str().to_string()));
+        }
+        if self.email {
+            attributes.push(("type".to_string(), "email".to_string()));
+        }
+        if self.url {
+            attributes.push(("type".to_string(), "url".to_string()));
+        }
+
+        attributes
+    }
+
+    /// Builds a validator checking values against these constraints. Empty values only fail the
+    /// `required` constraint.
+    pub fn validator(&self) -> impl Fn(&serde_json::Value) -> ValidationResult + Send + Sync {
+        let constraints = self.clone();
+
+        move |value| {
+            if is_empty_value(value) {
+                return if constraints.required {
+                    ValidationResult::Invalid("This field is required".to_string())
+                } else {
+                    ValidationResult::Valid
+                };
+            }
+
+            // Decimals are kept as strings so they're parsed to be compared with the bounds
+            let number = value.as_f64().or_else(|| {
+                value
+                    .as_str()
+                    .filter(|_| constraints.min.is_some() || constraints.max.is_some())
+                    .and_then(|text| text.trim().parse::<f64>().ok())
+            });
+            if let Some(number) = number {
+                if let Some(min) = constraints.min.filter(|min| number < *min) {
+                    return ValidationResult::Invalid(format!("Must be at least {}", min));
+                }
+                if let Some(max) = constraints.max.filter(|max| number > *max) {
+                    return ValidationResult::Invalid(format!("Must be at most {}", max));
+                }
+            }
+
+            if let Some(text) = value.as_str() {
+                let length = text.chars().count();
+                if let Some(min_length) = constraints.min_length.filter(|min| length < *min) {
+                    return ValidationResult::Invalid(format!(
+                        "Must be at least {} characters",
+                        min_length
+                    ));
+                }
+                if let Some(max_length) = constraints.max_length.filter(|max| length > *max) {
+                    return ValidationResult::Invalid(format!(
+                        "Must be at most {} characters",
+                        max_length
+                    ));
+                }
+                if constraints
+                    .pattern
+                    .as_ref()
+                    .is_some_and(|pattern| !pattern.is_match(text))
+                {
+                    return ValidationResult::Invalid("Invalid format".to_string());
+                }
+                if constraints.email && !is_email(text) {
+                    return ValidationResult::Invalid("Must be a valid email address".to_string());
+                }
+                if constraints.url && url::Url::parse(text).is_err() {
+                    return ValidationResult::Invalid("Must be a valid url".to_string());
+                }
+            }
+
+            ValidationResult::Valid
+        }
+    }
+}
+
+/// Runs the validator of every editable field against the submitted values
+pub fn validate_fields(
+    field_configs: &[FieldConfig],
+    values: &JsonResourceItem,
+) -> ValidationErrors {
+    field_configs
+        .iter()
+        .fold(ValidationErrors::default(), |mut errors, field_config| {
+            let value = values
+                .get(&field_config.field_id)
+                .cloned()
+                .unwrap_or(serde_json::Value::Null);
+
+            if let Some(message) = field_config.validation_error(value) {
+                errors.add_field_error(field_config.field_id.clone(), message);
+            }
+
+            errors
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn validate_fields_collects_field_errors() {
+        let mut name_config =
+            FieldConfig::create_text_config("name".to_string(), "Name".to_string(), false);
+        if let Some(create_config) = &mut name_config.create_config {
+            create_config.validate = Arc::new(|value| match value.as_str() {
+                Some("") => ValidationResult::Invalid("Name is required".to_string()),
+                _ => ValidationResult::Valid,
+            });
+        }
+        let id_config =
+            FieldConfig::create_integer_config("id".to_string(), "Id".to_string(), true);
+
+        let values = serde_json::json!({ "name": "" });
+        let errors = validate_fields(&[name_config, id_config], values.as_object().unwrap());
+
+        assert_eq!(
+            errors.field_error("name").map(String::as_str),
+            Some("Name is required")
+        );
+        assert_eq!(errors.field_errors.len(), 1);
+    }
+
+    fn is_valid(constraints: &FieldConstraints, value: serde_json::Value) -> bool {
+        matches!(constraints.validator()(&value), ValidationResult::Valid)
+    }
+
+    #[test]
+    fn constraints_validate_values() {
+        let age = FieldConstraints {
+            required: true,
+            min: Some(0.0),
+            max: Some(150.0),
+            ..Default::default()
+        };
+        assert!(is_valid(&age, serde_json::json!(30)));
+        assert!(!is_valid(&age, serde_json::json!(-1)));
+        assert!(!is_valid(&age, serde_json::json!(151)));
+        assert!(!is_valid(&age, serde_json::Value::Null));
+
+        let code = FieldConstraints {
+            min_length: Some(2),
+            max_length: Some(4),
+            pattern: Some(Pattern::new("[A-Z]+").unwrap()),
+            ..Default::default()
+        };
+        assert!(is_valid(&code, serde_json::json!("ABC")));
+        // Patterns have to match the whole value
+        assert!(!is_valid(&code, serde_json::json!("ABc")));
+        assert!(!is_valid(&code, serde_json::json!("A")));
+        assert!(!is_valid(&code, serde_json::json!("ABCDE")));
+        // Optional fields can be left empty
+        assert!(is_valid(&code, serde_json::json!("")));
+        // Invalid patterns are caught when they're built rather than when validating
+        assert!(Pattern::new("[A-Z").is_err());
+
+        let email = FieldConstraints {
+            email: true,
+            ..Default::default()
+        };
+        assert!(is_valid(&email, serde_json::json!("susan@example.com")));
+        assert!(!is_valid(&email, serde_json::json!("susan@example")));
+        assert!(!is_valid(&email, serde_json::json!("susan example.com")));
+
+        let url = FieldConstraints {
+            url: true,
+            ..Default::default()
+        };
+        assert!(is_valid(&url, serde_json::json!("https://example.com/a")));
+        assert!(!is_valid(&url, serde_json::json!("example.com")));
+    }
+
+    #[test]
+    fn constraints_html_attributes() {
+        let constraints = FieldConstraints {
+            required: true,
+            max_length: Some(64),
+            email: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            constraints.html_attributes(),
+            vec![
+                ("required".to_string(), String::new()),
+                ("maxlength".to_string(), "64".to_string()),
+                ("type".to_string(), "email".to_string()),
+            ]
+        );
+    }
+}
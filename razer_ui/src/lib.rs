@@ -1,11 +1,3 @@
-// #[derive(Template)]
-// #[template(path = "admin_home.html")]
-// struct AdminHomeTemplate {
-//     page_title: String,
-//     admin_title: String,
-//     resources: Vec<JsonResource>,
-// }
-
 use askama::Template;
 
 #[derive(Debug)]
@@ -13,19 +5,56 @@ pub enum RenderError {
     InternalError,
 }
 
+mod filters {
+    /// Hex encodes a value, e.g. an item id, for use in an element id. Ids can hold any character
+    /// but selectors can't match spaces, `#`, `.` or `:` unescaped, so both sides are encoded.
+    pub fn element_id<T: std::fmt::Display>(value: T) -> askama::Result<String> {
+        Ok(value
+            .to_string()
+            .bytes()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+}
+
 pub fn get_default_template_css() -> &'static str {
-    return include_str!("../styles/output.css");
+    include_str!("../styles/output.css")
 }
 
 pub fn render_text_widget(
     field_id: String,
     display_name: String,
     value: serde_json::Value,
+    attributes: Vec<(String, String)>,
 ) -> String {
+    let value = value.as_str().map(|val| val.to_string());
+
+    // Text areas don't support the pattern attribute or the email and url input types, so fields
+    // constrained by them are rendered as a single line input instead
+    let input_type = attributes
+        .iter()
+        .find(|(name, _)| name == "type")
+        .map(|(_, input_type)| input_type.clone());
+    if input_type.is_some() || attributes.iter().any(|(name, _)| name == "pattern") {
+        return TextInputWidget {
+            field_id,
+            display_name,
+            value,
+            input_type: input_type.unwrap_or_else(|| "text".to_string()),
+            attributes: attributes
+                .into_iter()
+                .filter(|(name, _)| name != "type")
+                .collect(),
+        }
+        .render()
+        .unwrap();
+    }
+
     TextAreaWidget {
-        field_id: field_id.clone(),
-        display_name: display_name.clone(),
-        value: value.as_str().map(|val| val.to_string()),
+        field_id,
+        display_name,
+        value,
+        attributes,
     }
     .render()
     .unwrap()
@@ -35,11 +64,13 @@ pub fn render_checkbox_widget(
     field_id: String,
     display_name: String,
     value: serde_json::Value,
+    attributes: Vec<(String, String)>,
 ) -> String {
     CheckboxWidget {
         field_id: field_id.clone(),
         display_name: display_name.clone(),
         value: value.as_bool(),
+        attributes,
     }
     .render()
     .unwrap()
@@ -49,11 +80,13 @@ pub fn render_toggle_widget(
     field_id: String,
     display_name: String,
     value: serde_json::Value,
+    attributes: Vec<(String, String)>,
 ) -> String {
     ToggleWidget {
         field_id: field_id.clone(),
         display_name: display_name.clone(),
         value: value.as_bool(),
+        attributes,
     }
     .render()
     .unwrap()
@@ -64,41 +97,414 @@ pub fn render_number_input_widget(
     field_id: String,
     display_name: String,
     value: serde_json::Value,
+    attributes: Vec<(String, String)>,
 ) -> String {
+    // Decimals are strings, as are values which failed to parse so they can be corrected
+    let value = match value {
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        serde_json::Value::String(value) => Some(value),
+        _ => None,
+    };
+
     NumberInputWidget {
         field_id: field_id.clone(),
         display_name: display_name.clone(),
-        value: value.as_i64(),
+        value,
+        attributes,
+    }
+    .render()
+    .unwrap()
+}
+
+/// Renders a select for picking one of the options, given as `(value, label)` pairs
+pub fn render_select_widget(
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    options: Vec<(String, String)>,
+    attributes: Vec<(String, String)>,
+) -> String {
+    SelectWidget {
+        field_id,
+        display_name,
+        value,
+        options,
+        attributes,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "widgets/select.html")]
+struct SelectWidget {
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    options: Vec<(String, String)>,
+    attributes: Vec<(String, String)>,
+}
+
+/// Renders a radio button for each of the options, given as `(value, label)` pairs
+pub fn render_radio_widget(
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    options: Vec<(String, String)>,
+    attributes: Vec<(String, String)>,
+) -> String {
+    RadioWidget {
+        field_id,
+        display_name,
+        value,
+        options,
+        attributes,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "widgets/radio.html")]
+struct RadioWidget {
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    options: Vec<(String, String)>,
+    attributes: Vec<(String, String)>,
+}
+
+/// Renders a date, time or datetime input. With `utc` the value is RFC 3339 in UTC, which is
+/// shown in the browser's local time and converted back to UTC when it's edited.
+pub fn render_date_time_widget(
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    input_type: String,
+    utc: bool,
+    attributes: Vec<(String, String)>,
+) -> String {
+    DateTimeInputWidget {
+        field_id,
+        display_name,
+        value,
+        input_type,
+        utc,
+        attributes,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "widgets/date_time_input.html")]
+struct DateTimeInputWidget {
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    input_type: String,
+    utc: bool,
+    attributes: Vec<(String, String)>,
+}
+
+/// Renders the autocomplete for a relation field. The id is kept in a hidden input and picked by
+/// searching the related resource at `search_endpoint`, which returns the options rendered by
+/// [`render_relation_options`]. The current item's label is loaded from `label_endpoint`.
+pub fn render_relation_widget(
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    search_endpoint: String,
+    label_endpoint: Option<String>,
+    attributes: Vec<(String, String)>,
+) -> String {
+    RelationWidget {
+        field_id,
+        display_name,
+        value,
+        search_endpoint,
+        label_endpoint,
+        attributes,
     }
     .render()
     .unwrap()
 }
 
+#[derive(Template)]
+#[template(path = "widgets/relation.html")]
+struct RelationWidget {
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    search_endpoint: String,
+    label_endpoint: Option<String>,
+    attributes: Vec<(String, String)>,
+}
+
+/// Renders the items matching a relation field's search, given as `(id, label)` pairs
+pub fn render_relation_options(options: Vec<(String, String)>) -> String {
+    RelationOptionsTemplate { options }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "widgets/relation_options.html")]
+struct RelationOptionsTemplate {
+    options: Vec<(String, String)>,
+}
+
+/// Renders a list of values as chips, given as `(value, label_endpoint)` pairs. The values are
+/// submitted as a json array of strings. With a `search_endpoint` the values are picked by
+/// searching it as with [`render_relation_widget`] and each chip's label is loaded from its
+/// endpoint, otherwise they're typed in.
+pub fn render_chips_widget(
+    field_id: String,
+    display_name: String,
+    values: Vec<(String, Option<String>)>,
+    search_endpoint: Option<String>,
+    attributes: Vec<(String, String)>,
+) -> String {
+    ChipsWidget {
+        field_id,
+        display_name,
+        json_value: serde_json::to_string(
+            &values.iter().map(|(value, _)| value).collect::<Vec<_>>(),
+        )
+        .unwrap(),
+        values,
+        search_endpoint,
+        attributes,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "widgets/chips.html")]
+struct ChipsWidget {
+    field_id: String,
+    display_name: String,
+    json_value: String,
+    values: Vec<(String, Option<String>)>,
+    search_endpoint: Option<String>,
+    attributes: Vec<(String, String)>,
+}
+
+/// Renders the label of a relation field's current item, as loaded by the relation widget
+pub fn render_relation_label(label: String) -> String {
+    askama::MarkupDisplay::new_unsafe(label, askama::Html).to_string()
+}
+
+/// Shown in place of fields which have no value, e.g. `None` fields in the list and detail views
+pub const EMPTY_VALUE_PLACEHOLDER: &str = "—";
+
+/// The name of the checkbox a nullable field is marked as empty with
+pub fn empty_field_name(field_id: &str) -> String {
+    format!("{}__empty", field_id)
+}
+
+/// Wraps the widget of a nullable field with an "Empty" checkbox, which submits the field as null
+/// when checked. Editing the widget unchecks it.
+pub fn render_nullable_widget(field_id: String, widget: String, is_empty: bool) -> String {
+    NullableWidget {
+        empty_field_name: empty_field_name(&field_id),
+        widget,
+        is_empty,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "widgets/nullable.html")]
+struct NullableWidget {
+    empty_field_name: String,
+    widget: String,
+    is_empty: bool,
+}
+
+/// Renders a field without a value in the detail view
+pub fn render_empty_value(display_name: String) -> String {
+    DisplayValueTemplate {
+        display_name,
+        value: EMPTY_VALUE_PLACEHOLDER.to_string(),
+        is_empty: true,
+        link: None,
+        chips: vec![],
+    }
+    .render()
+    .unwrap()
+}
+
+/// Renders a field's value as text in the detail view, for fields which format their values
+pub fn render_display_value(display_name: String, value: String) -> String {
+    DisplayValueTemplate {
+        display_name,
+        value,
+        is_empty: false,
+        link: None,
+        chips: vec![],
+    }
+    .render()
+    .unwrap()
+}
+
+/// Renders a field's value in the detail view as a link, e.g. to the related item of a relation
+/// field
+pub fn render_display_link(display_name: String, value: String, link: String) -> String {
+    DisplayValueTemplate {
+        display_name,
+        value,
+        is_empty: false,
+        link: Some(link),
+        chips: vec![],
+    }
+    .render()
+    .unwrap()
+}
+
+/// Renders the values of a list field in the detail view as chips
+pub fn render_display_chips(display_name: String, chips: Vec<AdminChip>) -> String {
+    DisplayValueTemplate {
+        display_name,
+        value: String::new(),
+        is_empty: false,
+        link: None,
+        chips,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "display_value.html")]
+struct DisplayValueTemplate {
+    display_name: String,
+    value: String,
+    is_empty: bool,
+    link: Option<String>,
+    chips: Vec<AdminChip>,
+}
+
+/// One of the values of a list field, shown in the list and detail views
+pub struct AdminChip {
+    pub label: String,
+    // The related item, for fields relating to many items
+    pub link: Option<String>,
+}
+
 pub struct AdminListTemplateRow {
-    pub data: Vec<String>,
+    pub item_id: String,
+    pub data: Vec<AdminListTemplateCell>,
     pub item_link: String,
 }
 
+pub struct AdminListTemplateCell {
+    pub value: String,
+    // Where the value links to, e.g. the related item for relation fields. The first cell always
+    // links to the row's item instead
+    pub link: Option<String>,
+    // The values of list fields, shown instead of the value when there are any
+    pub chips: Vec<AdminChip>,
+}
+
+pub struct AdminListTemplateHeader {
+    pub display_name: String,
+    // Link to sort the list by this column, `None` if the column is not sortable
+    pub sort_link: Option<String>,
+    // "asc" or "desc" if the list is currently sorted by this column
+    pub sort_direction: Option<String>,
+}
+
+pub enum AdminListFilterInput {
+    Boolean {
+        value: Option<bool>,
+    },
+    NumberRange {
+        min: Option<String>,
+        max: Option<String>,
+    },
+    Text {
+        contains: Option<String>,
+        starts_with: Option<String>,
+    },
+    Relation {
+        id: Option<String>,
+    },
+}
+
+pub struct AdminListFilter {
+    pub field_id: String,
+    pub display_name: String,
+    pub input: AdminListFilterInput,
+}
+
+pub struct AdminListSearch {
+    pub term: String,
+    // Query parameters to keep when searching, e.g. the current filters
+    pub hidden_params: Vec<(String, String)>,
+}
+
+pub struct AdminListFilters {
+    // Url the filter and search forms are submitted to
+    pub list_endpoint: String,
+    pub filters: Vec<AdminListFilter>,
+    // Query parameters to keep when the filter form is submitted, e.g. the current sort
+    pub hidden_params: Vec<(String, String)>,
+    // `None` if the resource has no searchable fields
+    pub search: Option<AdminListSearch>,
+}
+
+pub struct AdminListPagination {
+    pub page: u64,
+    pub page_count: Option<u64>,
+    pub total: Option<u64>,
+    pub previous_link: Option<String>,
+    pub next_link: Option<String>,
+}
+
+/// A link to one of the pages leading to the current one, e.g. the list an item is in. The
+/// current page itself has no link.
+pub struct AdminBreadcrumb {
+    pub label: String,
+    pub link: Option<String>,
+}
+
 #[derive(Template)]
 #[template(path = "admin_list.html")]
 struct AdminListTemplate {
     page_title: String,
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    description: Option<String>,
     create_view_endpoint: String,
-    headers: Vec<String>,
+    delete_endpoint: String,
+    headers: Vec<AdminListTemplateHeader>,
     rows: Vec<AdminListTemplateRow>,
+    pagination: AdminListPagination,
+    filters: AdminListFilters,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_list_resource_view(
     resource_name: String,
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    description: Option<String>,
     create_view_endpoint: String,
+    delete_endpoint: String,
     // field_widgets: Vec<String>
-    headers: Vec<String>,
+    headers: Vec<AdminListTemplateHeader>,
     rows: Vec<AdminListTemplateRow>,
+    pagination: AdminListPagination,
+    filters: AdminListFilters,
 ) -> String {
     AdminListTemplate {
         page_title: resource_name,
+        breadcrumbs,
+        description,
         create_view_endpoint,
+        delete_endpoint,
         rows,
+        pagination,
+        filters,
         headers,
         // fields: field_widgets,
     }
@@ -106,10 +512,41 @@ pub fn render_list_resource_view(
     .unwrap()
 }
 
-pub fn render_view_resource_view(resource_name: String, field_widgets: Vec<String>) -> String {
+/// Renders only the rows of the list view (and the pagination, swapped out of band) so searching
+/// can update the table without reloading the page
+pub fn render_list_rows_fragment(
+    rows: Vec<AdminListTemplateRow>,
+    pagination: AdminListPagination,
+) -> String {
+    AdminListRowsFragmentTemplate { rows, pagination }
+        .render()
+        .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "list_rows_fragment.html")]
+struct AdminListRowsFragmentTemplate {
+    rows: Vec<AdminListTemplateRow>,
+    pagination: AdminListPagination,
+}
+
+/// Renders the detail view. Each of the related list endpoints is loaded into its own section once
+/// the page has loaded, see [`render_related_list`].
+pub fn render_view_resource_view(
+    resource_name: String,
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    edit_view_endpoint: String,
+    delete_endpoint: String,
+    field_widgets: Vec<String>,
+    related_list_endpoints: Vec<String>,
+) -> String {
     AdminViewTemplate {
         page_title: resource_name,
+        breadcrumbs,
+        edit_view_endpoint,
+        delete_endpoint,
         fields: field_widgets,
+        related_list_endpoints,
     }
     .render()
     .unwrap()
@@ -119,27 +556,135 @@ pub fn render_view_resource_view(resource_name: String, field_widgets: Vec<Strin
 #[template(path = "admin_view.html")]
 struct AdminViewTemplate {
     page_title: String,
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    edit_view_endpoint: String,
+    delete_endpoint: String,
     fields: Vec<String>,
+    related_list_endpoints: Vec<String>,
+}
+
+pub struct AdminRelatedList {
+    pub title: String,
+    // The create view of the related resource, prefilled with the item being viewed
+    pub create_link: String,
+    // The related resource's own list view, filtered to the item being viewed
+    pub list_link: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<AdminListTemplateRow>,
+    // The page links load the related list endpoint again, in place
+    pub pagination: AdminListPagination,
+}
+
+/// Renders a list of related items on the detail view, replacing the section it's loaded into
+pub fn render_related_list(related_list: AdminRelatedList) -> String {
+    RelatedListTemplate { related_list }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "related_list.html")]
+struct RelatedListTemplate {
+    related_list: AdminRelatedList,
+}
+
+/// Renders the outcome of a bulk delete. Rows for the deleted ids are removed from the list using
+/// out of band swaps.
+pub fn render_bulk_delete_result(deleted_ids: Vec<String>, failed_count: usize) -> String {
+    BulkDeleteResultTemplate {
+        deleted_ids,
+        failed_count,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "bulk_delete_result.html")]
+struct BulkDeleteResultTemplate {
+    deleted_ids: Vec<String>,
+    failed_count: usize,
+}
+
+pub struct AdminFormField {
+    pub field_id: String,
+    pub html: String,
+    pub read_only: bool,
+    pub error: Option<String>,
+    // Endpoint the field is posted to when it loses focus, to validate it inline
+    pub validate_endpoint: Option<String>,
+}
+
+pub struct AdminForm {
+    pub endpoint: String,
+    pub submit_label: String,
+    pub fields: Vec<AdminFormField>,
+    // Errors which apply to the form as a whole rather than a single field
+    pub errors: Vec<String>,
 }
 
 pub fn render_create_resource_view(
     resource_name: String,
-    create_endpoint: String,
-    field_widgets: Vec<String>
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    form: AdminForm,
 ) -> String {
     AdminCreateTemplate {
         page_title: resource_name,
-        create_endpoint,
-        fields: field_widgets,
-    }.render().unwrap()
+        breadcrumbs,
+        form,
+    }
+    .render()
+    .unwrap()
 }
 
 #[derive(Template)]
 #[template(path = "admin_create.html")]
 struct AdminCreateTemplate {
     page_title: String,
-    fields: Vec<String>,
-    create_endpoint: String,
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    form: AdminForm,
+}
+
+pub fn render_edit_resource_view(
+    resource_name: String,
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    form: AdminForm,
+) -> String {
+    AdminEditTemplate {
+        page_title: resource_name,
+        breadcrumbs,
+        form,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "admin_edit.html")]
+struct AdminEditTemplate {
+    page_title: String,
+    breadcrumbs: Vec<AdminBreadcrumb>,
+    form: AdminForm,
+}
+
+/// Renders just the form, used to swap in the submitted form along with its validation errors
+pub fn render_form(form: AdminForm) -> String {
+    AdminFormTemplate { form }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "form_fragment.html")]
+struct AdminFormTemplate {
+    form: AdminForm,
+}
+
+/// Renders a single form field, used to validate fields inline as they lose focus
+pub fn render_form_field(field: AdminFormField) -> String {
+    AdminFormFieldTemplate { field }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "form_field_fragment.html")]
+struct AdminFormFieldTemplate {
+    field: AdminFormField,
 }
 
 #[derive(Template)]
@@ -148,6 +693,17 @@ struct TextAreaWidget {
     field_id: String,
     display_name: String,
     value: Option<String>,
+    attributes: Vec<(String, String)>,
+}
+
+#[derive(Template)]
+#[template(path = "widgets/text_input.html")]
+struct TextInputWidget {
+    field_id: String,
+    display_name: String,
+    value: Option<String>,
+    input_type: String,
+    attributes: Vec<(String, String)>,
 }
 
 #[derive(Template)]
@@ -156,6 +712,7 @@ struct CheckboxWidget {
     field_id: String,
     display_name: String,
     value: Option<bool>,
+    attributes: Vec<(String, String)>,
 }
 
 #[derive(Template)]
@@ -164,6 +721,7 @@ struct ToggleWidget {
     field_id: String,
     display_name: String,
     value: Option<bool>,
+    attributes: Vec<(String, String)>,
 }
 
 #[derive(Template)]
@@ -171,19 +729,125 @@ struct ToggleWidget {
 struct NumberInputWidget {
     field_id: String,
     display_name: String,
-    value: Option<i64>,
+    value: Option<String>,
+    attributes: Vec<(String, String)>,
+}
+
+/// A registered resource as shown on the home page
+pub struct AdminResourceCard {
+    pub name: String,
+    pub description: Option<String>,
+    // The resource's list view
+    pub link: String,
+    // `None` if the resource's items can't be counted
+    pub count: Option<u64>,
+}
+
+/// Renders the home page, with a card for each resource below any dashboard widgets, which are
+/// already rendered
+pub fn render_admin_home(
+    admin_title: String,
+    resources: Vec<AdminResourceCard>,
+    widgets: Vec<String>,
+) -> String {
+    AdminHomeTemplate {
+        page_title: admin_title.clone(),
+        admin_title,
+        resources,
+        widgets,
+    }
+    .render()
+    .unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "admin_home.html")]
+struct AdminHomeTemplate {
+    page_title: String,
+    admin_title: String,
+    resources: Vec<AdminResourceCard>,
+    widgets: Vec<String>,
+}
+
+/// Renders a dashboard widget showing a single figure, e.g. the number of new orders, optionally
+/// linking to where it comes from
+pub fn render_stat_tile(title: String, value: String, link: Option<String>) -> String {
+    StatTileTemplate { title, value, link }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "dashboard/stat_tile.html")]
+struct StatTileTemplate {
+    title: String,
+    value: String,
+    link: Option<String>,
+}
+
+/// Renders a dashboard widget listing items, e.g. the latest orders, as labels linking to each
+/// item
+pub fn render_recent_items(title: String, items: Vec<(String, String)>) -> String {
+    RecentItemsTemplate { title, items }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "dashboard/recent_items.html")]
+struct RecentItemsTemplate {
+    title: String,
+    items: Vec<(String, String)>,
 }
 
-pub fn render_not_found_view() -> String {
-    NotFound {
-        page_title: "Not found".to_string(),
+/// Renders a dashboard widget which failed as a tile showing its error, so the rest of the home
+/// page still loads
+pub fn render_error_tile(title: String, messages: Vec<String>) -> String {
+    ErrorTileTemplate { title, messages }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "dashboard/error_tile.html")]
+struct ErrorTileTemplate {
+    title: String,
+    messages: Vec<String>,
+}
+
+pub fn render_error_view(title: String, messages: Vec<String>) -> String {
+    ErrorTemplate {
+        page_title: title.clone(),
+        title,
+        messages,
     }
     .render()
     .unwrap()
 }
 
 #[derive(Template)]
-#[template(path = "not_found.html")]
-struct NotFound {
+#[template(path = "error.html")]
+struct ErrorTemplate {
     page_title: String,
+    title: String,
+    messages: Vec<String>,
+}
+
+/// Renders an error as an alert, for showing errors from htmx requests within the current page
+pub fn render_error_fragment(title: String, messages: Vec<String>) -> String {
+    ErrorFragmentTemplate { title, messages }.render().unwrap()
+}
+
+#[derive(Template)]
+#[template(path = "error_fragment.html")]
+struct ErrorFragmentTemplate {
+    title: String,
+    messages: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleted_rows_are_removed_by_their_encoded_id() {
+        let html = render_bulk_delete_result(vec!["a b#1.c:d".to_string()], 0);
+
+        // The same id as the item's row in the list
+        assert!(html.contains(r#"<tr id="row-61206223312e633a64""#));
+    }
 }